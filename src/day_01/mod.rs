@@ -10,8 +10,7 @@ pub fn part_1(input: String) -> Result<String, Box<dyn std::error::Error>> {
     let differences: HashSet<isize> = entries.iter().map(|n| 2020 - n).collect();
     entries
         .into_iter()
-        .filter(|n| differences.get(n).is_some())
-        .nth(0)
+        .find(|n| differences.contains(n))
         .ok_or(AOCError::new("no result found").into())
         .map(|n| (n * (2020 - n)).to_string())
 }
@@ -35,8 +34,7 @@ pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
                     .collect::<HashSet<isize>>(),
             )
         })
-        .filter(|(_, ds)| ds.len() > 0)
-        .nth(0)
+        .find(|(_, ds)| !ds.is_empty())
         .ok_or(AOCError::new("no result found").into())
         .map(|(n, ds)| n * ds.into_iter().product::<isize>())
         .map(|i| i.to_string())
@@ -0,0 +1,217 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{Password, PasswordPolicy, PasswordRow, Rule, Target};
+use crate::errors::AOCError;
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+// upper bound on filler characters beyond what a policy needs
+const MAX_EXTRA: usize = 5;
+
+// tries at a password whose graphemes don't run together
+const MAX_ATTEMPTS: usize = 1000;
+
+pub struct PasswordGenerator {
+    rng: StdRng,
+}
+
+impl PasswordGenerator {
+    pub fn new(seed: u64) -> Self {
+        PasswordGenerator {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    fn any(&mut self) -> char {
+        *ALPHABET.choose(&mut self.rng).unwrap() as char
+    }
+
+    fn matching(&mut self, target: &Target) -> Result<String, AOCError<'static>> {
+        match target {
+            Target::Grapheme(g) => Ok(g.clone()),
+            Target::Class(gs) => gs
+                .choose(&mut self.rng)
+                .cloned()
+                .ok_or(AOCError::new("policy class is empty")),
+        }
+    }
+
+    fn fillers(target: &Target) -> Result<Vec<String>, AOCError<'static>> {
+        let fillers: Vec<String> = ALPHABET
+            .iter()
+            .map(|&b| (b as char).to_string())
+            .filter(|g| !target.matches(g))
+            .collect();
+        if fillers.is_empty() {
+            return Err(AOCError::new("policy matches every filler character"));
+        }
+        Ok(fillers)
+    }
+
+    fn filler(&mut self, fillers: &[String]) -> String {
+        fillers.choose(&mut self.rng).unwrap().clone()
+    }
+
+    pub fn password(
+        &mut self,
+        policy: &PasswordPolicy,
+        rule: Rule,
+        valid: bool,
+    ) -> Result<Password, AOCError<'static>> {
+        // a target such as a lone combining mark or a regional indicator can
+        // join the grapheme before it, so keep only passwords which segment
+        // back into the pieces they were built from
+        for _ in 0..MAX_ATTEMPTS {
+            let graphemes: Vec<String> = match rule {
+                Rule::Count => self.count_password(policy, valid)?,
+                Rule::Position => self.position_password(policy, valid)?,
+            };
+            let password: Password = graphemes.concat();
+            if password
+                .graphemes(true)
+                .eq(graphemes.iter().map(String::as_str))
+            {
+                return Ok(password);
+            }
+        }
+        Err(AOCError::new(
+            "policy target joins with neighbouring graphemes",
+        ))
+    }
+
+    fn count_password(
+        &mut self,
+        policy: &PasswordPolicy,
+        valid: bool,
+    ) -> Result<Vec<String>, AOCError<'static>> {
+        let count: usize = if valid {
+            if policy.min > policy.max {
+                return Err(AOCError::new("policy cannot be satisfied"));
+            }
+            self.rng.gen_range(policy.min..=policy.max)
+        } else if policy.min > 0 && self.rng.gen_bool(0.5) {
+            self.rng.gen_range(0..policy.min)
+        } else {
+            self.rng
+                .gen_range(policy.max + 1..=policy.max + 1 + MAX_EXTRA)
+        };
+        let fillers: Vec<String> = Self::fillers(&policy.target)?;
+        let extra: usize = self.rng.gen_range(0..=MAX_EXTRA);
+        let mut graphemes: Vec<String> = Vec::new();
+        for _ in 0..count {
+            graphemes.push(self.matching(&policy.target)?);
+        }
+        for _ in 0..extra {
+            graphemes.push(self.filler(&fillers));
+        }
+        graphemes.shuffle(&mut self.rng);
+        Ok(graphemes)
+    }
+
+    fn position_password(
+        &mut self,
+        policy: &PasswordPolicy,
+        valid: bool,
+    ) -> Result<Vec<String>, AOCError<'static>> {
+        // positions are 1-indexed, so a 0 is never a match
+        let unsatisfiable: bool = policy.min == 0 || policy.max == 0 || policy.min == policy.max;
+        if valid && unsatisfiable {
+            return Err(AOCError::new("policy cannot be satisfied"));
+        }
+        let fillers: Vec<String> = Self::fillers(&policy.target)?;
+        let len: usize = policy.min.max(policy.max) + self.rng.gen_range(0..=MAX_EXTRA);
+        let mut graphemes: Vec<String> = (0..len).map(|_| self.any().to_string()).collect();
+        if policy.min == 0 || policy.max == 0 {
+            return Ok(graphemes);
+        }
+        let at_min: bool = self.rng.gen_bool(0.5);
+        let at_max: bool = if valid { !at_min } else { at_min };
+        for (i, hit) in [(policy.min, at_min), (policy.max, at_max)].iter() {
+            graphemes[i - 1] = if *hit {
+                self.matching(&policy.target)?
+            } else {
+                self.filler(&fillers)
+            };
+        }
+        Ok(graphemes)
+    }
+
+    pub fn row(&mut self, rule: Rule) -> Result<PasswordRow, AOCError<'static>> {
+        let min: usize = self.rng.gen_range(1..=10);
+        let max: usize = self.rng.gen_range(min + 1..=min + 10);
+        let policy = PasswordPolicy {
+            min,
+            max,
+            target: Target::Grapheme(self.any().to_string()),
+        };
+        let valid: bool = self.rng.gen_bool(0.5);
+        let password: Password = self.password(&policy, rule, valid)?;
+        Ok(PasswordRow { policy, password })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_generated_passwords_match_intent() -> Result<(), Box<dyn std::error::Error>> {
+        let mut generator = PasswordGenerator::new(2020);
+        for policy in [
+            "1-3 a",
+            "2-9 c",
+            "0-2 b",
+            "4-4 z",
+            "1-2 e\u{301}",
+            "1-3 \u{301}",
+            "1-3 \u{1f1eb}",
+            "3-5 [aeiou]",
+        ]
+        .iter()
+        {
+            for &rule in [Rule::Count, Rule::Position].iter() {
+                for &valid in [true, false].iter() {
+                    let unsatisfiable: bool =
+                        rule == Rule::Position && valid && ["0-2 b", "4-4 z"].contains(policy);
+                    for _ in 0..50 {
+                        let policy = PasswordPolicy::try_from(policy.to_string())?;
+                        if unsatisfiable {
+                            assert!(generator.password(&policy, rule, valid).is_err());
+                            continue;
+                        }
+                        let password = generator.password(&policy, rule, valid)?;
+                        let row = PasswordRow::try_from(format!("{}: {}", policy, password))?;
+                        assert_eq!(row.is_valid(rule), valid, "{} ({:?})", row, rule);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_generator_is_reproducible() -> Result<(), Box<dyn std::error::Error>> {
+        let mut a = PasswordGenerator::new(7);
+        let mut b = PasswordGenerator::new(7);
+        for _ in 0..20 {
+            assert_eq!(
+                a.row(Rule::Position)?.to_string(),
+                b.row(Rule::Position)?.to_string()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsatisfiable_policy() -> Result<(), Box<dyn std::error::Error>> {
+        let mut generator = PasswordGenerator::new(0);
+        let policy = PasswordPolicy::try_from("4-4 z".to_string())?;
+        assert!(generator.password(&policy, Rule::Position, true).is_err());
+        Ok(())
+    }
+}
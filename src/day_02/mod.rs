@@ -1,12 +1,86 @@
 use std::convert::TryFrom;
 use std::error::Error;
+use std::fmt;
+
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::errors::AOCError;
 
+mod generate;
+
+use generate::PasswordGenerator;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Rule {
+    // part 1
+    Count,
+    // part 2
+    Position,
+}
+
+impl TryFrom<&str> for Rule {
+    type Error = AOCError<'static>;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        match string {
+            "count" | "1" => Ok(Rule::Count),
+            "position" | "2" => Ok(Rule::Position),
+            _ => Err(AOCError::new("unknown rule (expected count or position)")),
+        }
+    }
+}
+
+// a grapheme cluster, or a class like `[aeiou]`
+#[derive(Clone, Debug, PartialEq)]
+enum Target {
+    Grapheme(String),
+    Class(Vec<String>),
+}
+
+impl Target {
+    fn matches(&self, grapheme: &str) -> bool {
+        match self {
+            Target::Grapheme(g) => g == grapheme,
+            Target::Class(gs) => gs.iter().any(|g| g == grapheme),
+        }
+    }
+}
+
+impl TryFrom<&str> for Target {
+    type Error = AOCError<'static>;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        if string.len() > 2 && string.starts_with('[') && string.ends_with(']') {
+            return Ok(Target::Class(
+                string[1..string.len() - 1]
+                    .graphemes(true)
+                    .map(String::from)
+                    .collect(),
+            ));
+        }
+        let mut graphemes = string.graphemes(true);
+        match (graphemes.next(), graphemes.next()) {
+            (Some(g), None) => Ok(Target::Grapheme(g.to_string())),
+            _ => Err(AOCError::new(
+                "password policy target must be one grapheme or a [class]",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Grapheme(g) => write!(f, "{}", g),
+            Target::Class(gs) => write!(f, "[{}]", gs.concat()),
+        }
+    }
+}
+
 struct PasswordPolicy {
     min: usize,
     max: usize,
-    chr: char,
+    target: Target,
 }
 
 impl TryFrom<String> for PasswordPolicy {
@@ -14,24 +88,26 @@ impl TryFrom<String> for PasswordPolicy {
 
     fn try_from(string: String) -> Result<Self, Self::Error> {
         let v_0: Vec<&str> = string.splitn(2, "-").collect();
-        let min: usize = match v_0.get(0) {
+        let min: usize = match v_0.first() {
             Some(s) => Ok(s.parse::<usize>()?),
             None => Err(AOCError::new("line missing password policy min")),
         }?;
         let v_1: Vec<&str> = v_0.get(1).unwrap_or(&"").splitn(2, " ").collect();
-        let max: usize = match v_1.get(0) {
+        let max: usize = match v_1.first() {
             Some(s) => Ok(s.parse::<usize>()?),
             None => Err(AOCError::new("line missing password policy max")),
         }?;
-        let chr: char = match v_1.get(1) {
-            Some(s) => Ok(s.parse::<char>()?),
-            None => Err(AOCError::new("line missing password policy chr")),
+        let target: Target = match v_1.get(1) {
+            Some(s) => Target::try_from(*s),
+            None => Err(AOCError::new("line missing password policy target")),
         }?;
-        Ok(PasswordPolicy {
-            min: min,
-            max: max,
-            chr: chr,
-        })
+        Ok(PasswordPolicy { min, max, target })
+    }
+}
+
+impl fmt::Display for PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{} {}", self.min, self.max, self.target)
     }
 }
 
@@ -44,16 +120,34 @@ struct PasswordRow {
 
 impl PasswordRow {
     fn is_valid_1(&self) -> bool {
-        let count: usize = self.password.matches(self.policy.chr).count();
+        let count: usize = self
+            .password
+            .graphemes(true)
+            .filter(|g| self.policy.target.matches(g))
+            .count();
         self.policy.min <= count && count <= self.policy.max
     }
 
     fn is_valid_2(&self) -> bool {
-        let chars = self.password.chars().into_iter().collect::<Vec<char>>();
-        let a = chars.get(self.policy.min);
-        let b = chars.get(self.policy.max);
-        (a.is_some() && b.is_some())
-            && ((a == Some(&self.policy.chr)) ^ (b == Some(&self.policy.chr)))
+        let graphemes = self.password.graphemes(true).collect::<Vec<&str>>();
+        let at = |i: usize| i.checked_sub(1).and_then(|i| graphemes.get(i));
+        match (at(self.policy.min), at(self.policy.max)) {
+            (Some(a), Some(b)) => self.policy.target.matches(a) ^ self.policy.target.matches(b),
+            _ => false,
+        }
+    }
+
+    fn is_valid(&self, rule: Rule) -> bool {
+        match rule {
+            Rule::Count => self.is_valid_1(),
+            Rule::Position => self.is_valid_2(),
+        }
+    }
+}
+
+impl fmt::Display for PasswordRow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.policy, self.password)
     }
 }
 
@@ -62,12 +156,13 @@ impl TryFrom<String> for PasswordRow {
 
     fn try_from(s: String) -> Result<Self, Self::Error> {
         let v: Vec<&str> = s.splitn(2, ":").collect();
-        let policy: PasswordPolicy = PasswordPolicy::try_from(v.get(0).unwrap_or(&"").to_string())?;
-        let password: Password = v.get(1).unwrap_or(&"").to_string();
-        Ok(PasswordRow {
-            policy: policy,
-            password: password,
-        })
+        let policy: PasswordPolicy =
+            PasswordPolicy::try_from(v.first().unwrap_or(&"").to_string())?;
+        // drop the space after the colon on its own, in case the password starts
+        // with a combining mark that would otherwise cluster with it
+        let password: &str = v.get(1).unwrap_or(&"");
+        let password: Password = password.strip_prefix(' ').unwrap_or(password).to_string();
+        Ok(PasswordRow { policy, password })
     }
 }
 
@@ -77,7 +172,7 @@ pub fn part_1(input: String) -> Result<String, Box<dyn std::error::Error>> {
         .map(|line| PasswordRow::try_from(line.to_string()))
         .collect::<Result<Vec<PasswordRow>, _>>()?
         .into_iter()
-        .filter(|row| row.is_valid_1())
+        .filter(|row| row.is_valid(Rule::Count))
         .collect::<Vec<PasswordRow>>()
         .len()
         .to_string())
@@ -89,12 +184,44 @@ pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
         .map(|line| PasswordRow::try_from(line.to_string()))
         .collect::<Result<Vec<PasswordRow>, _>>()?
         .into_iter()
-        .filter(|row| row.is_valid_2())
+        .filter(|row| row.is_valid(Rule::Position))
         .collect::<Vec<PasswordRow>>()
         .len()
         .to_string())
 }
 
+fn generate(args: &[String]) -> Result<String, Box<dyn Error>> {
+    let lines: usize = args
+        .first()
+        .ok_or(AOCError::new("missing argument for lines"))?
+        .parse()?;
+    let seed: u64 = args
+        .get(1)
+        .ok_or(AOCError::new("missing argument for seed"))?
+        .parse()?;
+    let rule: Rule = match args.get(2) {
+        Some(s) => Rule::try_from(s.as_str())?,
+        None => Rule::Count,
+    };
+    let mut generator = PasswordGenerator::new(seed);
+    Ok((0..lines)
+        .map(|_| generator.row(rule).map(|row| row.to_string()))
+        .collect::<Result<Vec<String>, _>>()?
+        .join("\n"))
+}
+
+// commands: `generate <lines> <seed> [count|position]`
+pub fn command(
+    name: &str,
+    args: &[String],
+    _input: Option<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match name {
+        "generate" => generate(args),
+        _ => Err(AOCError::new("unknown command").into()),
+    }
+}
+
 pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
     Ok(format!(
         "{}\n{}",
@@ -102,3 +229,41 @@ pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
         part_2(input.clone())?
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(s: &str) -> Result<PasswordRow, Box<dyn std::error::Error>> {
+        PasswordRow::try_from(s.to_string())
+    }
+
+    #[test]
+    fn test_part_1_example() -> Result<(), Box<dyn std::error::Error>> {
+        let input = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+        assert_eq!(part_1(input.to_string())?.parse::<usize>()?, 2);
+        assert_eq!(part_2(input.to_string())?.parse::<usize>()?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_grapheme_targets() -> Result<(), Box<dyn std::error::Error>> {
+        // "e" followed by a combining acute accent is one grapheme, not two chars
+        assert!(row("2-2 e\u{301}: e\u{301}xe\u{301}")?.is_valid_1());
+        assert!(!row("1-1 e: e\u{301}")?.is_valid_1());
+        assert!(row("1-3 \u{1f44d}\u{1f3fd}: \u{1f44d}\u{1f3fd}ab")?.is_valid_2());
+        assert!(row("1-3 \u{301}: \u{301}ab")?.is_valid_2());
+        assert!(row("1-1 \u{301}: \u{301}ab")?.is_valid_1());
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_targets() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(row("2-3 [aeiou]: bake")?.is_valid_1());
+        assert!(!row("2-3 [aeiou]: boo-boo")?.is_valid_1());
+        assert!(row("1-2 [xy]: ay")?.is_valid_2());
+        assert!(!row("1-2 [xy]: xy")?.is_valid_2());
+        assert!(row("1-2 ab: abc").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use std::convert::TryFrom;
+
+use super::Trees;
+use crate::errors::AOCError;
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// `right/down`, kept in lowest terms
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fraction {
+    pub right: usize,
+    pub down: usize,
+}
+
+impl Fraction {
+    pub fn new(right: usize, down: usize) -> Result<Self, AOCError<'static>> {
+        if down == 0 {
+            return Err(AOCError::new("slope must move down"));
+        }
+        let g: usize = gcd(right, down);
+        Ok(Fraction {
+            right: right / g,
+            down: down / g,
+        })
+    }
+}
+
+impl TryFrom<&str> for Fraction {
+    type Error = AOCError<'static>;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let e = AOCError::new("bad slope (expected right/down)");
+        let mut parts = string.splitn(2, '/');
+        let right: usize = parts
+            .next()
+            .ok_or(e.clone())?
+            .parse()
+            .map_err(|_| e.clone())?;
+        let down: usize = match parts.next() {
+            Some(s) => s.parse().map_err(|_| e.clone())?,
+            None => 1,
+        };
+        Fraction::new(right, down)
+    }
+}
+
+impl Trees {
+    fn tree_at(&self, x: isize, y: isize) -> Result<bool, AOCError<'static>> {
+        if !(0..self.h as isize).contains(&y) {
+            return Err(AOCError::new("y out of bounds"));
+        }
+        Ok(*self.m[y as usize]
+            .get(x.rem_euclid(self.w as isize) as usize)
+            .ok_or(AOCError::new("x out of bounds"))?)
+    }
+
+    // every lattice point on the line, so `2/4` checks every other row
+    pub fn walk_exact(&self, slope: Fraction) -> Result<usize, AOCError<'static>> {
+        self.walk(slope.right, slope.down)
+    }
+
+    // endpoints included; `x` wraps, `y` must be on the map
+    pub fn line_of_sight(
+        &self,
+        from: (isize, isize),
+        to: (isize, isize),
+    ) -> Result<usize, AOCError<'static>> {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let g = gcd(dx.unsigned_abs(), dy.unsigned_abs()) as isize;
+        // a zero-length segment is the single point `from`
+        let (sx, sy) = if g == 0 { (0, 0) } else { (dx / g, dy / g) };
+        let mut c: usize = 0;
+        for i in 0..=g {
+            if self.tree_at(from.0 + i * sx, from.1 + i * sy)? {
+                c += 1;
+            }
+        }
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    #[test]
+    fn test_fraction_parse() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(Fraction::try_from("4/6")?, Fraction { right: 2, down: 3 });
+        assert_eq!(Fraction::try_from("3")?, Fraction { right: 3, down: 1 });
+        assert!(Fraction::try_from("1/0").is_err());
+        assert!(Fraction::try_from("a/2").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_exact() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from(TEST_INPUT.to_string())?;
+        assert_eq!(
+            trees.walk_exact(Fraction::try_from("2/4")?)?,
+            trees.walk(1, 2)?
+        );
+        assert_eq!(trees.walk_exact(Fraction::try_from("3/1")?)?, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_of_sight() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from(TEST_INPUT.to_string())?;
+        // the (3, 1) route, walked in either direction
+        assert_eq!(trees.line_of_sight((0, 0), (30, 10))?, 7);
+        assert_eq!(trees.line_of_sight((30, 10), (0, 0))?, 7);
+        // straight along the top row: "..##......."
+        assert_eq!(trees.line_of_sight((0, 0), (10, 0))?, 2);
+        assert_eq!(trees.line_of_sight((2, 0), (2, 0))?, 1);
+        assert!(trees.line_of_sight((0, 0), (0, 11)).is_err());
+        Ok(())
+    }
+}
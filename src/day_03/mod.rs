@@ -2,6 +2,19 @@ use std::convert::TryFrom;
 
 use crate::errors::AOCError;
 
+mod line;
+mod packed;
+mod render;
+mod sweep;
+mod topology;
+
+use line::Fraction;
+use packed::PackedTrees;
+use render::RenderMode;
+use topology::{Edge, Topology};
+
+type Slope = (usize, usize);
+
 #[derive(Clone)]
 struct Trees {
     w: usize,
@@ -11,22 +24,7 @@ struct Trees {
 
 impl Trees {
     fn walk(&self, slope_x: usize, slope_y: usize) -> Result<usize, AOCError<'static>> {
-        let mut x: usize = 0;
-        let mut c: usize = 0;
-        for i in (0..self.h).step_by(slope_y) {
-            let p: bool = *self
-                .m
-                .get(i)
-                .ok_or(AOCError::new("y out of bounds"))?
-                .get(x)
-                .ok_or(AOCError::new("x out of bounds"))?;
-            if p {
-                c += 1;
-            }
-            x += slope_x;
-            x %= self.w;
-        }
-        Ok(c)
+        self.walk_with(slope_x, slope_y, Topology::default())
     }
 }
 
@@ -45,7 +43,6 @@ impl TryFrom<String> for Trees {
         let h = string.as_bytes().iter().filter(|&&c| c == b'\n').count();
         let m = string
             .lines()
-            .into_iter()
             .collect::<Vec<&str>>()
             .into_iter()
             .map(|l| {
@@ -61,7 +58,7 @@ impl TryFrom<String> for Trees {
                     .collect::<Result<Vec<bool>, AOCError>>()
             })
             .collect::<Result<Vec<Vec<bool>>, AOCError>>()?;
-        Ok(Trees { w: w, h: h, m: m })
+        Ok(Trees { w, h, m })
     }
 }
 
@@ -71,7 +68,7 @@ pub fn part_1(input: String) -> Result<String, Box<dyn std::error::Error>> {
 
 pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
     let trees = Trees::try_from(input)?;
-    let slopes: Vec<(usize, usize)> = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+    let slopes: Vec<Slope> = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
     Ok(slopes
         .into_iter()
         .try_fold::<usize, _, Result<usize, AOCError>>(1, |acc, (slope_x, slope_y)| {
@@ -80,6 +77,129 @@ pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
         .to_string())
 }
 
+fn sweep(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let max_x: usize = args
+        .first()
+        .ok_or(AOCError::new("missing argument for max_x"))?
+        .parse()?;
+    let max_y: usize = args
+        .get(1)
+        .ok_or(AOCError::new("missing argument for max_y"))?
+        .parse()?;
+    let sweep = Trees::try_from(input)?.sweep(0..=max_x, 1..=max_y)?;
+    match args.get(2).map(|s| s.as_str()) {
+        Some("csv") => Ok(sweep.to_csv().trim_end().to_string()),
+        Some(_) => Err(AOCError::new("unknown sweep format").into()),
+        None => Ok(sweep.to_string().trim_end().to_string()),
+    }
+}
+
+fn slope(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let slope = Fraction::try_from(
+        args.first()
+            .ok_or(AOCError::new("missing argument for slope"))?
+            .as_str(),
+    )?;
+    Ok(Trees::try_from(input)?.walk_exact(slope)?.to_string())
+}
+
+fn sight(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let ns = args
+        .iter()
+        .map(|s| s.parse::<isize>())
+        .collect::<Result<Vec<isize>, _>>()?;
+    match ns[..] {
+        [x0, y0, x1, y1] => Ok(Trees::try_from(input)?
+            .line_of_sight((x0, y0), (x1, y1))?
+            .to_string()),
+        _ => Err(AOCError::new("expected four coordinates").into()),
+    }
+}
+
+fn parallel(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let threads: usize = match args.first() {
+        Some(s) => s.parse()?,
+        None => std::thread::available_parallelism()?.get(),
+    };
+    let trees = PackedTrees::try_from(input.as_str())?;
+    Ok(trees
+        .walk_parallel(&[(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)], threads)?
+        .into_iter()
+        .product::<usize>()
+        .to_string())
+}
+
+fn render(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let slope_x: usize = args
+        .first()
+        .ok_or(AOCError::new("missing argument for slope_x"))?
+        .parse()?;
+    let slope_y: usize = args
+        .get(1)
+        .ok_or(AOCError::new("missing argument for slope_y"))?
+        .parse()?;
+    if slope_y == 0 {
+        return Err(AOCError::new("slope y must be at least 1").into());
+    }
+    let mode: RenderMode = match args.get(2).map(|s| s.as_str()) {
+        Some("full") | None => RenderMode::Full,
+        Some("traversed") => RenderMode::Traversed,
+        Some(_) => return Err(AOCError::new("unknown render mode").into()),
+    };
+    let trees = Trees::try_from(input)?;
+    match args.get(3) {
+        Some(path) => {
+            std::fs::write(path, trees.render_ppm(slope_x, slope_y, mode)?)?;
+            Ok(format!("wrote {}", path))
+        }
+        None => Ok(trees.render(slope_x, slope_y, mode)?),
+    }
+}
+
+fn walk(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let slope_x: usize = args
+        .first()
+        .ok_or(AOCError::new("missing argument for slope_x"))?
+        .parse()?;
+    let slope_y: usize = args
+        .get(1)
+        .ok_or(AOCError::new("missing argument for slope_y"))?
+        .parse()?;
+    let mut topology = Topology::default();
+    if let Some(s) = args.get(2) {
+        topology.horizontal = Edge::try_from(s.as_str())?;
+    }
+    if let Some(s) = args.get(3) {
+        topology.vertical = Edge::try_from(s.as_str())?;
+    }
+    Ok(Trees::try_from(input)?
+        .walk_with(slope_x, slope_y, topology)?
+        .to_string())
+}
+
+// commands: `sweep <max_x> <max_y> [csv]`
+//           `slope <right>/<down>`
+//           `sight <x0> <y0> <x1> <y1>`
+//           `parallel [threads]`
+//           `render <slope_x> <slope_y> [full|traversed] [<path>.ppm]`
+//           `walk <slope_x> <slope_y> [<horizontal edge> [<vertical edge>]]`
+pub fn command(
+    name: &str,
+    args: &[String],
+    input: Option<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let input = input.ok_or(AOCError::new("input file missing"));
+    match name {
+        "parallel" => parallel(args, input?),
+        "render" => render(args, input?),
+        "sight" => sight(args, input?),
+        "slope" => slope(args, input?),
+        "sweep" => sweep(args, input?),
+        "walk" => walk(args, input?),
+        _ => Err(AOCError::new("unknown command").into()),
+    }
+}
+
 pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
     Ok(format!(
         "{}\n{}",
@@ -92,7 +212,7 @@ pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = concat!(
+    pub(super) const TEST_INPUT: &str = concat!(
         "..##.......\n",
         "#...#...#..\n",
         ".#....#..#.\n",
@@ -0,0 +1,171 @@
+use std::convert::TryFrom;
+use std::thread;
+
+use bitvec::prelude::*;
+
+use super::Slope;
+use crate::errors::AOCError;
+
+pub struct PackedTrees {
+    w: usize,
+    h: usize,
+    bits: BitVec,
+}
+
+impl TryFrom<&str> for PackedTrees {
+    type Error = AOCError<'static>;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let w: usize = string
+            .lines()
+            .next()
+            .ok_or(AOCError::new("bad input"))?
+            .len();
+        let h: usize = string.lines().count();
+        let mut bits: BitVec = bitvec![0; w * h];
+        for (y, l) in string.lines().enumerate() {
+            if l.len() != w {
+                return Err(AOCError::new("tree map rows differ in length"));
+            }
+            for (x, c) in l.bytes().enumerate() {
+                match c {
+                    b'.' => {}
+                    b'#' => bits.set(y * w + x, true),
+                    _ => return Err(AOCError::new("unexpected character in tree map")),
+                }
+            }
+        }
+        Ok(PackedTrees { w, h, bits })
+    }
+}
+
+impl PackedTrees {
+    // picks up each walk wherever it is on row `start`
+    fn walk_rows(&self, slopes: &[Slope], start: usize, end: usize) -> Vec<usize> {
+        slopes
+            .iter()
+            .map(|&(slope_x, slope_y)| {
+                // first row of the chunk that the walk lands on
+                let k_0: usize = start.div_ceil(slope_y);
+                let mut x: usize = (k_0 % self.w) * (slope_x % self.w) % self.w;
+                let mut c: usize = 0;
+                for y in (k_0 * slope_y..end).step_by(slope_y) {
+                    if self.bits[y * self.w + x] {
+                        c += 1;
+                    }
+                    x = (x + slope_x) % self.w;
+                }
+                c
+            })
+            .collect()
+    }
+
+    pub fn walk_parallel(
+        &self,
+        slopes: &[Slope],
+        threads: usize,
+    ) -> Result<Vec<usize>, AOCError<'static>> {
+        if self.w == 0 || self.h == 0 {
+            return Err(AOCError::new("empty tree map"));
+        }
+        if slopes.iter().any(|&(_, y)| y == 0) {
+            return Err(AOCError::new("slope y must be at least 1"));
+        }
+        let chunk: usize = self.h / threads.max(1) + 1;
+        let counts: Vec<Vec<usize>> = thread::scope(|s| {
+            (0..self.h)
+                .step_by(chunk)
+                .map(|start| {
+                    s.spawn(move || self.walk_rows(slopes, start, self.h.min(start + chunk)))
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        Ok((0..slopes.len())
+            .map(|i| counts.iter().map(|c| c[i]).sum())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::super::Trees;
+    use super::*;
+
+    use std::time::Instant;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const SLOPES: [Slope; 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+    #[test]
+    fn test_walk_parallel_example() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from(TEST_INPUT.to_string())?;
+        let packed = PackedTrees::try_from(TEST_INPUT)?;
+        for threads in 1..=12 {
+            assert_eq!(packed.walk_parallel(&SLOPES, threads)?, vec![2, 7, 3, 4, 2]);
+        }
+        let uneven: Vec<Slope> = vec![(2, 3), (13, 4), (0, 5)];
+        for threads in 1..=12 {
+            let counts = packed.walk_parallel(&uneven, threads)?;
+            for (i, &(x, y)) in uneven.iter().enumerate() {
+                assert_eq!(counts[i], trees.walk(x, y)?);
+            }
+        }
+        assert!(PackedTrees::try_from("#.\n#\n").is_err());
+        assert!(PackedTrees::try_from("#.\n#x\n").is_err());
+        let empty = PackedTrees::try_from("\n")?;
+        assert_eq!(
+            empty.walk_parallel(&SLOPES, 2).err().map(|e| e.to_string()),
+            Some("empty tree map".to_string())
+        );
+        Ok(())
+    }
+
+    fn random_map(w: usize, h: usize) -> String {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut map = String::with_capacity((w + 1) * h);
+        for _ in 0..h {
+            for _ in 0..w {
+                map.push(if rng.gen_bool(0.2) { '#' } else { '.' });
+            }
+            map.push('\n');
+        }
+        map
+    }
+
+    // cargo test --release bench_walk_parallel -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_walk_parallel() -> Result<(), Box<dyn std::error::Error>> {
+        let map: String = random_map(31, 4_000_000);
+        let t = Instant::now();
+        let packed = PackedTrees::try_from(map.as_str())?;
+        println!("packing:                {:?}", t.elapsed());
+        let t = Instant::now();
+        let trees = Trees::try_from(map)?;
+        println!("parsing:                {:?}", t.elapsed());
+
+        let t = Instant::now();
+        let sequential = SLOPES
+            .iter()
+            .map(|&(x, y)| trees.walk(x, y))
+            .collect::<Result<Vec<usize>, _>>()?;
+        println!("sequential:             {:?}", t.elapsed());
+
+        let mut threads: Vec<usize> = vec![1, 2, 4, thread::available_parallelism()?.get()];
+        threads.sort_unstable();
+        threads.dedup();
+        for &n in threads.iter() {
+            let t = Instant::now();
+            let parallel = packed.walk_parallel(&SLOPES, n)?;
+            println!("parallel ({:2} threads): {:?}", n, t.elapsed());
+            assert_eq!(sequential, parallel);
+        }
+        Ok(())
+    }
+}
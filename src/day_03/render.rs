@@ -0,0 +1,156 @@
+use super::Trees;
+use crate::errors::AOCError;
+
+// pixels per map cell in PPM output
+const SCALE: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RenderMode {
+    Full,
+    Traversed,
+}
+
+impl Trees {
+    // `x` not yet wrapped onto the map
+    pub fn path(&self, slope_x: usize, slope_y: usize) -> Vec<(usize, usize)> {
+        (0..self.h)
+            .step_by(slope_y)
+            .enumerate()
+            .map(|(i, y)| (i * slope_x, y))
+            .collect()
+    }
+
+    fn render_rows(
+        &self,
+        slope_x: usize,
+        slope_y: usize,
+        mode: RenderMode,
+    ) -> Result<Vec<Vec<char>>, AOCError<'static>> {
+        if self.w == 0 || self.h == 0 {
+            return Err(AOCError::new("empty tree map"));
+        }
+        if slope_y == 0 {
+            return Err(AOCError::new("slope y must be at least 1"));
+        }
+        let path = self.path(slope_x, slope_y);
+        let tiles: usize = path.last().map(|(x, _)| x / self.w + 1).unwrap_or(1);
+        let mut rows: Vec<Vec<char>> = self.m[..self.h]
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&tree| if tree { '#' } else { '.' })
+                    .cycle()
+                    .take(tiles * self.w)
+                    .collect()
+            })
+            .collect();
+        for &(x, y) in path.iter() {
+            let tree: bool = *self
+                .m
+                .get(y)
+                .ok_or(AOCError::new("y out of bounds"))?
+                .get(x % self.w)
+                .ok_or(AOCError::new("x out of bounds"))?;
+            rows[y][x] = if tree { 'X' } else { 'O' };
+        }
+        Ok(match mode {
+            RenderMode::Full => rows,
+            RenderMode::Traversed => path
+                .iter()
+                .map(|&(x, y)| {
+                    let start: usize = x / self.w * self.w;
+                    rows[y][start..start + self.w].to_vec()
+                })
+                .collect(),
+        })
+    }
+
+    pub fn render(
+        &self,
+        slope_x: usize,
+        slope_y: usize,
+        mode: RenderMode,
+    ) -> Result<String, AOCError<'static>> {
+        Ok(self
+            .render_rows(slope_x, slope_y, mode)?
+            .into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n"))
+    }
+
+    pub fn render_ppm(
+        &self,
+        slope_x: usize,
+        slope_y: usize,
+        mode: RenderMode,
+    ) -> Result<Vec<u8>, AOCError<'static>> {
+        let rows = self.render_rows(slope_x, slope_y, mode)?;
+        let w: usize = rows.first().map(|r| r.len()).unwrap_or(0);
+        let mut ppm: Vec<u8> =
+            format!("P6\n{} {}\n255\n", w * SCALE, rows.len() * SCALE).into_bytes();
+        for row in rows.iter() {
+            for _ in 0..SCALE {
+                for c in row.iter() {
+                    let rgb: [u8; 3] = match c {
+                        '#' => [34, 139, 34],
+                        'X' => [220, 20, 60],
+                        'O' => [65, 105, 225],
+                        _ => [255, 255, 255],
+                    };
+                    for _ in 0..SCALE {
+                        ppm.extend_from_slice(&rgb);
+                    }
+                }
+            }
+        }
+        Ok(ppm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_render_example() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from(TEST_INPUT.to_string())?;
+        let rendered = trees.render(3, 1, RenderMode::Full)?;
+        let rows: Vec<&str> = rendered.lines().collect();
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[0], "O.##.........##.........##.......");
+        assert!(rows[1].starts_with("#..O#...#..#"));
+        assert!(rows[2].starts_with(".#....X..#..#"));
+        assert_eq!(rendered.matches('X').count(), trees.walk(3, 1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_traversed() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from(TEST_INPUT.to_string())?;
+        let rendered = trees.render(1, 2, RenderMode::Traversed)?;
+        let rows: Vec<&str> = rendered.lines().collect();
+        assert_eq!(rows.len(), 6);
+        assert!(rows.iter().all(|r| r.len() == 11));
+        assert_eq!(rows[1], ".X....#..#.");
+        let ppm = trees.render_ppm(1, 2, RenderMode::Traversed)?;
+        assert!(ppm.starts_with(b"P6\n44 24\n255\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_bad_maps() -> Result<(), Box<dyn std::error::Error>> {
+        for map in ["..#\n#..\n\n", "..#\n#\n..#\n"].iter() {
+            let trees = Trees::try_from(map.to_string())?;
+            assert!(trees.walk(1, 1).is_err());
+            assert!(trees.render(1, 1, RenderMode::Full).is_err());
+            assert!(trees.render_ppm(1, 1, RenderMode::Traversed).is_err());
+        }
+        let empty = Trees::try_from("\n".to_string())?;
+        assert!(empty.render(1, 1, RenderMode::Full).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use super::{Slope, Trees};
+use crate::errors::AOCError;
+
+pub struct Sweep {
+    pub hits: Vec<(Slope, usize)>,
+}
+
+impl Trees {
+    pub fn sweep(
+        &self,
+        xs: RangeInclusive<usize>,
+        ys: RangeInclusive<usize>,
+    ) -> Result<Sweep, AOCError<'static>> {
+        if ys.contains(&0) {
+            return Err(AOCError::new("slope y must be at least 1"));
+        }
+        let mut hits: Vec<(Slope, usize)> = Vec::new();
+        for slope_y in ys {
+            for slope_x in xs.clone() {
+                hits.push(((slope_x, slope_y), self.walk(slope_x, slope_y)?));
+            }
+        }
+        Ok(Sweep { hits })
+    }
+}
+
+impl Sweep {
+    fn slopes_with(&self, count: usize) -> Vec<Slope> {
+        self.hits
+            .iter()
+            .filter(|(_, c)| *c == count)
+            .map(|(slope, _)| *slope)
+            .collect()
+    }
+
+    pub fn fewest(&self) -> Option<(usize, Vec<Slope>)> {
+        let count: usize = self.hits.iter().map(|(_, c)| *c).min()?;
+        Some((count, self.slopes_with(count)))
+    }
+
+    pub fn most(&self) -> Option<(usize, Vec<Slope>)> {
+        let count: usize = self.hits.iter().map(|(_, c)| *c).max()?;
+        Some((count, self.slopes_with(count)))
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("slope_x,slope_y,trees\n");
+        for ((x, y), c) in self.hits.iter() {
+            csv.push_str(&format!("{},{},{}\n", x, y, c));
+        }
+        csv
+    }
+}
+
+impl fmt::Display for Sweep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (label, extreme) in [("fewest", self.fewest()), ("most", self.most())].iter() {
+            if let Some((count, slopes)) = extreme {
+                let slopes: Vec<String> = slopes
+                    .iter()
+                    .map(|(x, y)| format!("({}, {})", x, y))
+                    .collect();
+                writeln!(f, "{} trees: {} at {}", label, count, slopes.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_sweep_example() -> Result<(), Box<dyn std::error::Error>> {
+        let sweep = Trees::try_from(TEST_INPUT.to_string())?.sweep(1..=7, 1..=2)?;
+        assert_eq!(sweep.hits.len(), 14);
+        assert!(sweep.hits.contains(&((3, 1), 7)));
+        assert!(sweep.hits.contains(&((1, 2), 2)));
+        assert_eq!(sweep.most(), Some((7, vec![(3, 1)])));
+        assert!(sweep.to_csv().starts_with("slope_x,slope_y,trees\n1,1,2\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sweep_rejects_zero_y() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from(TEST_INPUT.to_string())?;
+        assert!(trees.sweep(0..=3, 0..=1).is_err());
+        Ok(())
+    }
+}
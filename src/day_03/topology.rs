@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+use super::Trees;
+use crate::errors::AOCError;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edge {
+    // re-enter from the opposite side
+    Wrap,
+    // stay on the last row or column
+    Clamp,
+    // end the walk
+    Stop,
+    // bounce back, reversing direction along that axis
+    Reflect,
+}
+
+impl TryFrom<&str> for Edge {
+    type Error = AOCError<'static>;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        match string {
+            "wrap" => Ok(Edge::Wrap),
+            "clamp" => Ok(Edge::Clamp),
+            "stop" => Ok(Edge::Stop),
+            "reflect" => Ok(Edge::Reflect),
+            _ => Err(AOCError::new(
+                "unknown edge (expected wrap, clamp, stop or reflect)",
+            )),
+        }
+    }
+}
+
+impl Edge {
+    // `None` if the walk ends
+    fn step(self, p: isize, v: isize, n: isize) -> Option<(isize, isize)> {
+        let (mut p, mut v) = (p + v, v);
+        if (0..n).contains(&p) {
+            return Some((p, v));
+        }
+        match self {
+            Edge::Wrap => Some((p.rem_euclid(n), v)),
+            Edge::Clamp => Some((p.clamp(0, n - 1), v)),
+            Edge::Stop => None,
+            Edge::Reflect if n == 1 => Some((0, -v)),
+            Edge::Reflect => {
+                while !(0..n).contains(&p) {
+                    p = if p < 0 { -p } else { 2 * (n - 1) - p };
+                    v = -v;
+                }
+                Some((p, v))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Topology {
+    pub horizontal: Edge,
+    pub vertical: Edge,
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Topology {
+            horizontal: Edge::Wrap,
+            vertical: Edge::Stop,
+        }
+    }
+}
+
+impl Trees {
+    // counts a tree each time the walk lands on it, until the position and
+    // velocity repeat, so a reflected walk can count a square twice
+    pub fn walk_with(
+        &self,
+        slope_x: usize,
+        slope_y: usize,
+        topology: Topology,
+    ) -> Result<usize, AOCError<'static>> {
+        if self.w == 0 || self.h == 0 {
+            return Err(AOCError::new("empty tree map"));
+        }
+        let (w, h) = (self.w as isize, self.h as isize);
+        let (mut x, mut y, mut vx, mut vy): (isize, isize, isize, isize) =
+            (0, 0, slope_x as isize, slope_y as isize);
+        // nothing can repeat if every step moves down and the bottom edge stops
+        let track: bool = topology.vertical != Edge::Stop || slope_y == 0;
+        let mut seen: HashSet<(isize, isize, isize, isize)> = HashSet::new();
+        let mut c: usize = 0;
+        loop {
+            if track && !seen.insert((x, y, vx, vy)) {
+                break;
+            }
+            let p: bool = *self
+                .m
+                .get(y as usize)
+                .ok_or(AOCError::new("y out of bounds"))?
+                .get(x as usize)
+                .ok_or(AOCError::new("x out of bounds"))?;
+            if p {
+                c += 1;
+            }
+            match (
+                topology.horizontal.step(x, vx, w),
+                topology.vertical.step(y, vy, h),
+            ) {
+                (Some((nx, nvx)), Some((ny, nvy))) => {
+                    x = nx;
+                    vx = nvx;
+                    y = ny;
+                    vy = nvy;
+                }
+                _ => break,
+            }
+        }
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_default_topology_matches_walk() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from(TEST_INPUT.to_string())?;
+        for &((x, y), c) in [
+            ((1, 1), 2),
+            ((3, 1), 7),
+            ((5, 1), 3),
+            ((7, 1), 4),
+            ((1, 2), 2),
+        ]
+        .iter()
+        {
+            assert_eq!(trees.walk_with(x, y, Topology::default())?, c);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_edges() {
+        assert_eq!(Edge::Wrap.step(9, 3, 11), Some((1, 3)));
+        assert_eq!(Edge::Clamp.step(9, 3, 11), Some((10, 3)));
+        assert_eq!(Edge::Stop.step(9, 3, 11), None);
+        assert_eq!(Edge::Reflect.step(9, 3, 11), Some((8, -3)));
+        assert_eq!(Edge::Reflect.step(1, -3, 11), Some((2, 3)));
+        assert_eq!(Edge::Reflect.step(0, 25, 11), Some((5, 25)));
+    }
+
+    #[test]
+    fn test_bounded_walks() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from(TEST_INPUT.to_string())?;
+        let stop = Topology {
+            horizontal: Edge::Stop,
+            vertical: Edge::Stop,
+        };
+        // (0,0) (3,1) (6,2) (9,3), then off the right edge
+        assert_eq!(trees.walk_with(3, 1, stop)?, 1);
+        let torus = Topology {
+            horizontal: Edge::Wrap,
+            vertical: Edge::Wrap,
+        };
+        // 11x11 torus with a (1, 1) slope visits the main diagonal once
+        assert_eq!(trees.walk_with(1, 1, torus)?, trees.walk(1, 1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reflect_revisits() -> Result<(), Box<dyn std::error::Error>> {
+        let trees = Trees::try_from("#..\n".to_string())?;
+        let reflect = Topology {
+            horizontal: Edge::Reflect,
+            vertical: Edge::Stop,
+        };
+        // (0,0) (1,0) (2,0) (1,0) (0,0), then (1,0) moving right again
+        assert_eq!(trees.walk_with(1, 0, reflect)?, 2);
+        Ok(())
+    }
+}
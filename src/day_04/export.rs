@@ -0,0 +1,162 @@
+use std::convert::TryFrom;
+
+use super::schema::Schema;
+use super::Passport;
+use crate::errors::AOCError;
+use crate::input::blocks;
+use crate::json;
+
+// schema fields in schema order, then any other keys alphabetically
+pub struct Table {
+    columns: Vec<String>,
+    rows: Vec<Row>,
+}
+
+struct Row {
+    index: usize,
+    values: Vec<Option<String>>,
+    complete: bool,
+    valid: bool,
+}
+
+impl Table {
+    pub fn new(schema: &Schema, input: &str) -> Result<Self, AOCError<'static>> {
+        let passports = blocks(input)
+            .into_iter()
+            .map(|(_, block)| Passport::try_from(block))
+            .collect::<Result<Vec<Passport>, _>>()?;
+        let mut columns: Vec<String> = schema.keys().iter().map(|k| k.to_string()).collect();
+        let mut extra: Vec<String> = passports
+            .iter()
+            .flat_map(|p| p.0.keys())
+            .filter(|k| !columns.iter().any(|c| c == *k))
+            .map(|k| k.to_string())
+            .collect();
+        extra.sort();
+        extra.dedup();
+        columns.extend(extra);
+        let rows: Vec<Row> = passports
+            .iter()
+            .enumerate()
+            .map(|(i, p)| Row {
+                index: i + 1,
+                values: columns
+                    .iter()
+                    .map(|c| p.0.get(c.as_str()).map(|v| v.to_string()))
+                    .collect(),
+                complete: schema.is_complete(p),
+                valid: schema.is_valid(p),
+            })
+            .collect();
+        Ok(Table { columns, rows })
+    }
+
+    pub fn to_csv(&self) -> String {
+        let columns: Vec<String> = self.columns.iter().map(|c| csv_escape(c)).collect();
+        let mut csv = format!("index,{},complete,valid\n", columns.join(","));
+        for row in self.rows.iter() {
+            let values: Vec<String> = row
+                .values
+                .iter()
+                .map(|v| v.as_deref().map(csv_escape).unwrap_or(NULL.to_string()))
+                .collect();
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                row.index,
+                values.join(","),
+                row.complete,
+                row.valid
+            ));
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let objects: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut members: Vec<String> = vec![format!("\"index\":{}", row.index)];
+                for (c, v) in self.columns.iter().zip(row.values.iter()) {
+                    let value = match v {
+                        Some(v) => json::string(v),
+                        None => "null".to_string(),
+                    };
+                    members.push(format!("{}:{}", json::string(c), value));
+                }
+                members.push(format!("\"complete\":{}", row.complete));
+                members.push(format!("\"valid\":{}", row.valid));
+                format!("  {{{}}}", members.join(","))
+            })
+            .collect();
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
+
+// missing fields, as postgres and mysql write them; a value which reads the
+// same, or is empty, is quoted
+const NULL: &str = "\\N";
+
+fn csv_escape(s: &str) -> String {
+    if s.is_empty() || s == NULL || s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    #[test]
+    fn test_csv_example() -> Result<(), Box<dyn std::error::Error>> {
+        let csv = Table::new(&Schema::default(), TEST_INPUT)?.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[0],
+            "index,byr,iyr,eyr,hgt,hcl,ecl,pid,cid,complete,valid"
+        );
+        assert_eq!(
+            lines[2],
+            "2,1929,2013,2023,\\N,#cfa07d,amb,028048884,350,false,false"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_example() -> Result<(), Box<dyn std::error::Error>> {
+        let json = Table::new(&Schema::default(), "zzz:a\"b byr:1990\n\nhgt:1,2")?.to_json();
+        assert_eq!(
+            json,
+            concat!(
+                "[\n",
+                "  {\"index\":1,\"byr\":\"1990\",\"iyr\":null,\"eyr\":null,\"hgt\":null,",
+                "\"hcl\":null,\"ecl\":null,\"pid\":null,\"cid\":null,\"zzz\":\"a\\\"b\",",
+                "\"complete\":false,\"valid\":false},\n",
+                "  {\"index\":2,\"byr\":null,\"iyr\":null,\"eyr\":null,\"hgt\":\"1,2\",",
+                "\"hcl\":null,\"ecl\":null,\"pid\":null,\"cid\":null,\"zzz\":null,",
+                "\"complete\":false,\"valid\":false}\n",
+                "]\n",
+            )
+        );
+        let csv = Table::new(&Schema::default(), "zzz:a\"b byr:1990\n\nhgt:1,2")?.to_csv();
+        assert!(csv.contains("\n2,\\N,\\N,\\N,\"1,2\","));
+        assert!(csv.contains(",\"a\"\"b\",false,false\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_nulls() -> Result<(), Box<dyn std::error::Error>> {
+        let csv = Table::new(&Schema::default(), "byr:1990 pid:\n\nbyr:1991 cid:\\N")?.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[1], "1,1990,\\N,\\N,\\N,\\N,\\N,\"\",\\N,false,false");
+        assert_eq!(
+            lines[2],
+            "2,1991,\\N,\\N,\\N,\\N,\\N,\\N,\"\\N\",false,false"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::errors::AOCError;
+
+const REQUIRED: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+const EYE_COLORS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+const HEX: &[u8] = b"0123456789abcdef";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Intent {
+    Valid,
+    MissingField,
+    InvalidValue,
+}
+
+const INTENTS: [Intent; 3] = [Intent::Valid, Intent::MissingField, Intent::InvalidValue];
+
+pub struct PassportGenerator {
+    rng: StdRng,
+}
+
+impl PassportGenerator {
+    pub fn new(seed: u64) -> Self {
+        PassportGenerator {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    fn digits(&mut self, n: usize) -> String {
+        (0..n)
+            .map(|_| char::from(b'0' + self.rng.gen_range(0..10)))
+            .collect()
+    }
+
+    fn hex(&mut self, n: usize) -> String {
+        (0..n)
+            .map(|_| *HEX.choose(&mut self.rng).unwrap() as char)
+            .collect()
+    }
+
+    fn year(&mut self, min: u32, max: u32, valid: bool) -> String {
+        if valid {
+            return self.rng.gen_range(min..=max).to_string();
+        }
+        match self.rng.gen_range(0..3) {
+            0 => self.rng.gen_range(min - 50..min).to_string(),
+            1 => self.rng.gen_range(max + 1..=max + 50).to_string(),
+            _ => self.digits(2),
+        }
+    }
+
+    fn value(&mut self, key: &str, valid: bool) -> String {
+        match (key, valid) {
+            ("byr", _) => self.year(1920, 2002, valid),
+            ("iyr", _) => self.year(2010, 2020, valid),
+            ("eyr", _) => self.year(2020, 2030, valid),
+            ("hgt", true) => match self.rng.gen_bool(0.5) {
+                true => format!("{}cm", self.rng.gen_range(150..=193)),
+                false => format!("{}in", self.rng.gen_range(59..=76)),
+            },
+            ("hgt", false) => match self.rng.gen_range(0..3) {
+                0 => format!("{}cm", self.rng.gen_range(194..=250)),
+                1 => format!("{}in", self.rng.gen_range(20..59)),
+                _ => self.rng.gen_range(59..=193).to_string(),
+            },
+            ("hcl", true) => format!("#{}", self.hex(6)),
+            ("hcl", false) => match self.rng.gen_range(0..3) {
+                0 => self.hex(6),
+                1 => format!("#{}", self.hex(5)),
+                _ => format!("#{}z", self.hex(5)),
+            },
+            ("ecl", true) => EYE_COLORS.choose(&mut self.rng).unwrap().to_string(),
+            ("ecl", false) => ["wat", "xry", "gmt", "zzz"]
+                .choose(&mut self.rng)
+                .unwrap()
+                .to_string(),
+            ("pid", true) => self.digits(9),
+            ("pid", false) => match self.rng.gen_bool(0.5) {
+                true => self.digits(8),
+                false => self.digits(10),
+            },
+            ("cid", _) => self.rng.gen_range(100..=350).to_string(),
+            _ => unreachable!("no generator for key {}", key),
+        }
+    }
+
+    pub fn passport(&mut self, intent: Intent) -> String {
+        let mut keys: Vec<&str> = REQUIRED.to_vec();
+        keys.shuffle(&mut self.rng);
+        let n: usize = self.rng.gen_range(1..=3);
+        let (missing, invalid): (usize, usize) = match intent {
+            Intent::Valid => (0, 0),
+            Intent::MissingField => (n, 0),
+            Intent::InvalidValue => (0, n),
+        };
+        let mut fields: Vec<String> = keys[missing..]
+            .iter()
+            .enumerate()
+            .map(|(i, k)| format!("{}:{}", k, self.value(k, i >= invalid)))
+            .collect();
+        if self.rng.gen_bool(0.5) {
+            fields.push(format!("cid:{}", self.value("cid", true)));
+        }
+        fields.shuffle(&mut self.rng);
+        let mut record = String::new();
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                record.push(if self.rng.gen_bool(0.25) { '\n' } else { ' ' });
+            }
+            record.push_str(field);
+        }
+        record
+    }
+
+    pub fn batch(
+        &mut self,
+        n: usize,
+        weights: [u32; 3],
+    ) -> Result<Vec<(Intent, String)>, AOCError<'static>> {
+        let distribution = WeightedIndex::new(weights.iter())
+            .map_err(|_| AOCError::new("weights must not all be zero"))?;
+        Ok((0..n)
+            .map(|_| {
+                let intent = INTENTS[distribution.sample(&mut self.rng)];
+                (intent, self.passport(intent))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::schema::Schema;
+    use super::super::Passport;
+    use super::*;
+
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_verdict_matches_intent() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = Schema::default();
+        let mut generator = PassportGenerator::new(2020);
+        for (intent, record) in generator.batch(3000, [1, 1, 1])? {
+            let passport = Passport::try_from(record.as_str())?;
+            assert_eq!(
+                schema.is_complete(&passport),
+                intent != Intent::MissingField,
+                "{:?}: {}",
+                intent,
+                record
+            );
+            assert_eq!(
+                schema.is_valid(&passport),
+                intent == Intent::Valid,
+                "{:?}: {}",
+                intent,
+                record
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_proportions() -> Result<(), Box<dyn std::error::Error>> {
+        let mut generator = PassportGenerator::new(4);
+        let batch = generator.batch(100, [0, 1, 0])?;
+        assert!(batch.iter().all(|(i, _)| *i == Intent::MissingField));
+        let batch = generator.batch(1000, [3, 1, 0])?;
+        let valid = batch.iter().filter(|(i, _)| *i == Intent::Valid).count();
+        assert!((650..850).contains(&valid), "{} valid", valid);
+        assert!(generator.batch(1, [0, 0, 0]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_generator_is_reproducible() -> Result<(), Box<dyn std::error::Error>> {
+        let a = PassportGenerator::new(7).batch(20, [2, 1, 1])?;
+        let b = PassportGenerator::new(7).batch(20, [2, 1, 1])?;
+        assert_eq!(a, b);
+        Ok(())
+    }
+}
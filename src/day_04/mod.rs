@@ -1,46 +1,22 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
 
-use regex::Regex;
-
 use crate::errors::AOCError;
-
-enum Unit {
-    Inches,
-    Centimeters,
-}
-
-struct Height {
-    unit: Unit,
-    value: usize,
-}
-
-impl TryFrom<&str> for Height {
-    type Error = AOCError<'static>;
-
-    fn try_from(string: &str) -> Result<Self, Self::Error> {
-        let unit = {
-            if string.ends_with("cm") {
-                Ok(Unit::Centimeters)
-            } else if string.ends_with("in") {
-                Ok(Unit::Inches)
-            } else {
-                Err(AOCError::new("bad units"))
-            }
-        }?;
-        let value = Regex::new(r"\d+")
-            .unwrap()
-            .find(string)
-            .ok_or(AOCError::new("missing height digits"))?
-            .as_str()
-            .parse::<usize>()
-            .map_err(|_| AOCError::new("invalid height"))?;
-        Ok(Height {
-            unit: unit,
-            value: value,
-        })
-    }
-}
+use crate::input::blocks;
+
+mod export;
+mod generate;
+mod report;
+mod schema;
+mod strict;
+mod typed;
+
+use export::Table;
+use generate::PassportGenerator;
+use report::Report;
+use schema::Schema;
+use strict::parse_strict;
+use typed::ValidatedPassport;
 
 struct Passport<'a>(HashMap<&'a str, &'a str>);
 
@@ -50,76 +26,146 @@ impl<'a> TryFrom<&'a str> for Passport<'a> {
     fn try_from(string: &'a str) -> Result<Self, Self::Error> {
         Ok(Passport(
             string
-                .clone()
                 .split_whitespace()
                 .map(|kv| match &kv.split(":").collect::<Vec<&str>>()[..] {
-                    &[k, v] => Ok((k.clone(), v.clone())),
-                    _ => {
-                        let s: &'static str = "";
-                        Err(AOCError::new(s))
-                    }
+                    &[k, v] => Ok((k, v)),
+                    _ => Err(AOCError::new("malformed key:value token")),
                 })
                 .collect::<Result<HashMap<&str, &str>, AOCError<'static>>>()?,
         ))
     }
 }
 
-impl<'a> Passport<'a> {
-    fn is_valid_1(&self) -> bool {
-        let keys = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
-        keys.iter().all(|k| self.0.contains_key(k))
-    }
+pub fn part_1(input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let schema = Schema::default();
+    Ok(blocks(&input)
+        .into_iter()
+        .try_fold::<usize, _, Result<usize, AOCError>>(0, |acc, (_, ls)| {
+            Ok(acc + (schema.is_complete(&Passport::try_from(ls)?) as usize))
+        })?
+        .to_string())
+}
 
-    fn is_valid_2(&self) -> bool {
-        // TODO: what is a more elegant method
-        // also could do all of these via regex...
-        let f = || -> Result<bool, Box<dyn std::error::Error>> {
-            let byr = self.0.get("byr").ok_or("")?.parse::<usize>()?;
-            let iyr = self.0.get("iyr").ok_or("")?.parse::<usize>()?;
-            let eyr = self.0.get("eyr").ok_or("")?.parse::<usize>()?;
-            let hgt = Height::try_from(*self.0.get("hgt").ok_or("")?)?;
-            let hcl = self.0.get("hcl").ok_or("")?;
-            let ecl = self.0.get("ecl").ok_or("")?;
-            let pid = self.0.get("pid").ok_or("missing pid")?;
-            Ok(self.is_valid_1()
-                && (1920..2003).contains(&byr)
-                && (2010..2021).contains(&iyr)
-                && (2020..2031).contains(&eyr)
-                && (match hgt.unit {
-                    Unit::Inches => (59..77).contains(&hgt.value),
-                    Unit::Centimeters => (150..194).contains(&hgt.value),
-                })
-                && Regex::new(r"\#[0-9a-f]{6}").unwrap().is_match(hcl)
-                && Regex::new(r"amb|blu|brn|gry|grn|hzl|oth")
-                    .unwrap()
-                    .is_match(ecl)
-                && pid.len() == 9)
-        };
-        match f() {
-            Ok(b) => b,
-            _ => false,
-        }
+pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let schema = Schema::default();
+    Ok(blocks(&input)
+        .into_iter()
+        .try_fold::<usize, _, Result<usize, AOCError>>(0, |acc, (_, ls)| {
+            Ok(acc + (schema.is_valid(&Passport::try_from(ls)?) as usize))
+        })?
+        .to_string())
+}
+
+// a schema file in the format of `default.schema`, or the default itself
+fn schema_from(path: Option<&String>) -> Result<Schema, Box<dyn std::error::Error>> {
+    match path {
+        Some(path) => Ok(Schema::try_from(std::fs::read_to_string(path)?.as_str())?),
+        None => Ok(Schema::default()),
     }
 }
 
-pub fn part_1(input: String) -> Result<String, Box<dyn std::error::Error>> {
-    Ok(input
-        .split("\n\n")
-        .try_fold::<usize, _, Result<usize, AOCError>>(0, |acc, ls| {
-            Ok(acc + (Passport::try_from(ls)?.is_valid_1() as usize))
+fn validate(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let schema: Schema = schema_from(args.first())?;
+    Ok(blocks(&input)
+        .into_iter()
+        .try_fold::<usize, _, Result<usize, AOCError>>(0, |acc, (_, ls)| {
+            Ok(acc + (schema.is_valid(&Passport::try_from(ls)?) as usize))
         })?
         .to_string())
 }
 
-pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
-    Ok(input
-        .split("\n\n")
-        .try_fold::<usize, _, Result<usize, AOCError>>(0, |acc, ls| {
-            Ok(acc + (Passport::try_from(ls)?.is_valid_2() as usize))
-        })?
+fn report(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let schema: Schema = schema_from(args.first())?;
+    Ok(Report::new(&schema, &input)?
+        .to_string()
+        .trim_end()
         .to_string())
 }
 
+fn export(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let schema: Schema = schema_from(args.get(1))?;
+    let table = Table::new(&schema, &input)?;
+    match args.first().map(|s| s.as_str()) {
+        Some("csv") => Ok(table.to_csv().trim_end().to_string()),
+        Some("json") => Ok(table.to_json().trim_end().to_string()),
+        _ => Err(AOCError::new("expected export format csv or json").into()),
+    }
+}
+
+fn typed(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let schema: Schema = schema_from(args.first())?;
+    let mut lines: Vec<String> = Vec::new();
+    for (i, (_, block)) in blocks(&input).into_iter().enumerate() {
+        if let Ok(passport) = ValidatedPassport::new(&Passport::try_from(block)?, &schema) {
+            lines.push(format!("passport {}: {}", i + 1, passport));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+fn strict(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let schema: Schema = schema_from(args.first())?;
+    match parse_strict(&input, &schema) {
+        Ok(passports) => Ok(format!("{} passports, no issues", passports.len())),
+        Err(issues) => Ok(issues
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")),
+    }
+}
+
+fn generate(args: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+    let n: usize = args
+        .first()
+        .ok_or(AOCError::new("missing argument for passports"))?
+        .parse()?;
+    let seed: u64 = args
+        .get(1)
+        .ok_or(AOCError::new("missing argument for seed"))?
+        .parse()?;
+    let mut weights: [u32; 3] = [1, 1, 1];
+    if let Some(proportions) = args.get(2) {
+        let ws: Vec<&str> = proportions.split(':').collect();
+        if ws.len() != 3 {
+            return Err(AOCError::new("expected proportions valid:missing:invalid").into());
+        }
+        for (w, s) in weights.iter_mut().zip(ws) {
+            *w = s.parse()?;
+        }
+    }
+    let mut generator = PassportGenerator::new(seed);
+    Ok(generator
+        .batch(n, weights)?
+        .into_iter()
+        .map(|(_, record)| record)
+        .collect::<Vec<String>>()
+        .join("\n\n"))
+}
+
+// commands: `validate [<schema file>]`
+//           `report [<schema file>]`
+//           `export csv|json [<schema file>]`
+//           `typed [<schema file>]`
+//           `strict [<schema file>]`
+//           `generate <passports> <seed> [<valid>:<missing>:<invalid>]`
+pub fn command(
+    name: &str,
+    args: &[String],
+    input: Option<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let input = input.ok_or(AOCError::new("input file missing"));
+    match name {
+        "export" => export(args, input?),
+        "generate" => generate(args),
+        "report" => report(args, input?),
+        "strict" => strict(args, input?),
+        "typed" => typed(args, input?),
+        "validate" => validate(args, input?),
+        _ => Err(AOCError::new("unknown command").into()),
+    }
+}
+
 pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
     Ok(format!(
         "{}\n{}",
@@ -132,7 +178,7 @@ pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = concat!(
+    pub(super) const TEST_INPUT: &str = concat!(
         "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\n",
         "byr:1937 iyr:2017 cid:147 hgt:183cm\n",
         "\n",
@@ -153,4 +199,53 @@ mod tests {
         assert_eq!(part_1(TEST_INPUT.to_string())?.parse::<usize>()?, 2);
         Ok(())
     }
+
+    const TEST_INPUT_INVALID: &str = concat!(
+        "eyr:1972 cid:100\n",
+        "hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926\n",
+        "\n",
+        "iyr:2019\n",
+        "hcl:#602927 eyr:1967 hgt:170cm\n",
+        "ecl:grn pid:012533040 byr:1946\n",
+        "\n",
+        "hcl:dab227 iyr:2012\n",
+        "ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277\n",
+        "\n",
+        "hgt:59cm ecl:zzz\n",
+        "eyr:2038 hcl:74454a iyr:2023\n",
+        "pid:3556412378 byr:2007\n",
+    );
+
+    const TEST_INPUT_VALID: &str = concat!(
+        "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980\n",
+        "hcl:#623a2f\n",
+        "\n",
+        "eyr:2029 ecl:blu cid:129 byr:1989\n",
+        "iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm\n",
+        "\n",
+        "hcl:#888785\n",
+        "hgt:164cm byr:2001 iyr:2015 cid:88\n",
+        "pid:545766238 ecl:hzl\n",
+        "eyr:2022\n",
+        "\n",
+        "iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719\n",
+    );
+
+    #[test]
+    fn test_part_2_example() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(part_2(TEST_INPUT_INVALID.to_string())?.parse::<usize>()?, 0);
+        assert_eq!(part_2(TEST_INPUT_VALID.to_string())?.parse::<usize>()?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_crlf_and_blank_separators() -> Result<(), Box<dyn std::error::Error>> {
+        let crlf = TEST_INPUT.replace('\n', "\r\n");
+        assert_eq!(part_1(crlf.trim_end().to_string())?.parse::<usize>()?, 2);
+        let spaced = TEST_INPUT.replace("\n\n", "\n  \t\n");
+        assert_eq!(part_1(spaced)?.parse::<usize>()?, 2);
+        let valid = TEST_INPUT_VALID.replace('\n', "\r\n");
+        assert_eq!(part_2(valid)?.parse::<usize>()?, 4);
+        Ok(())
+    }
 }
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::schema::{Schema, Violation};
+use super::Passport;
+use crate::errors::AOCError;
+use crate::input::blocks;
+
+struct Entry {
+    lines: (usize, usize),
+    violations: Vec<Violation>,
+}
+
+pub struct Report {
+    entries: Vec<Entry>,
+}
+
+impl Report {
+    pub fn new(schema: &Schema, input: &str) -> Result<Self, AOCError<'static>> {
+        let mut entries: Vec<Entry> = Vec::new();
+        for (lines, block) in blocks(input) {
+            entries.push(Entry {
+                lines,
+                violations: schema.violations(&Passport::try_from(block)?),
+            });
+        }
+        Ok(Report { entries })
+    }
+
+    pub fn valid(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.violations.is_empty())
+            .count()
+    }
+
+    // keyed like `"byr range"`
+    pub fn totals(&self) -> BTreeMap<String, usize> {
+        let mut totals: BTreeMap<String, usize> = BTreeMap::new();
+        for v in self.entries.iter().flat_map(|e| e.violations.iter()) {
+            *totals.entry(format!("{} {}", v.key, v.rule)).or_insert(0) += 1;
+        }
+        totals
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, e) in self.entries.iter().enumerate() {
+            write!(
+                f,
+                "passport {} (lines {}-{}): ",
+                i + 1,
+                e.lines.0,
+                e.lines.1
+            )?;
+            if e.violations.is_empty() {
+                writeln!(f, "ok")?;
+            } else {
+                let vs: Vec<String> = e.violations.iter().map(|v| v.to_string()).collect();
+                writeln!(f, "{}", vs.join("; "))?;
+            }
+        }
+        writeln!(f, "\nvalid: {} of {}", self.valid(), self.entries.len())?;
+        for (rule, count) in self.totals() {
+            writeln!(f, "{}: {}", rule, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    #[test]
+    fn test_report_example() -> Result<(), Box<dyn std::error::Error>> {
+        let report = Report::new(&Schema::default(), TEST_INPUT)?;
+        assert_eq!(report.valid(), 2);
+        let totals = report.totals();
+        assert_eq!(totals.get("hgt required"), Some(&1));
+        assert_eq!(totals.get("byr required"), Some(&1));
+        assert_eq!(totals.len(), 2);
+        let text = report.to_string();
+        assert!(text.starts_with("passport 1 (lines 1-2): ok\n"));
+        assert!(text.contains("passport 4 (lines 12-13): byr missing (required)\n"));
+        Ok(())
+    }
+}
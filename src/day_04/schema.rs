@@ -0,0 +1,425 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use regex::Regex;
+
+use super::Passport;
+
+pub const DEFAULT_SCHEMA: &str = include_str!("default.schema");
+
+#[derive(Clone, Debug)]
+pub struct SchemaError {
+    line: usize,
+    msg: &'static str,
+}
+
+impl SchemaError {
+    fn new(line: usize, msg: &'static str) -> Self {
+        SchemaError { line, msg }
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "schema line {}: {}", self.line, self.msg)
+    }
+}
+
+impl Error for SchemaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Problem {
+    Missing,
+    Unparseable,
+    OutOfRange,
+    BadFormat,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Problem::Missing => "missing",
+            Problem::Unparseable => "unparseable",
+            Problem::OutOfRange => "out of range",
+            Problem::BadFormat => "bad format",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub key: String,
+    pub rule: &'static str,
+    pub problem: Problem,
+    pub value: Option<String>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} ({})", self.key, self.problem, self.rule)?;
+        if let Some(value) = &self.value {
+            write!(f, ": {:?}", value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Rule {
+    Range(isize, isize),
+    // from consecutive `unit` lines; the value must satisfy one
+    Units(Vec<(String, isize, isize)>),
+    Regex(Regex),
+    OneOf(Vec<String>),
+    Length(usize),
+}
+
+// like `str::parse`, but without the leading `+` which it lets through
+fn integer(s: &str) -> Option<isize> {
+    if s.starts_with('+') {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn in_range(n: isize, min: isize, max: isize) -> Result<(), Problem> {
+    if min <= n && n <= max {
+        Ok(())
+    } else {
+        Err(Problem::OutOfRange)
+    }
+}
+
+impl Rule {
+    fn name(&self) -> &'static str {
+        match self {
+            Rule::Range(..) => "range",
+            Rule::Units(_) => "unit",
+            Rule::Regex(_) => "regex",
+            Rule::OneOf(_) => "one-of",
+            Rule::Length(_) => "length",
+        }
+    }
+
+    fn check(&self, value: &str) -> Result<(), Problem> {
+        let ok_if = |b: bool| if b { Ok(()) } else { Err(Problem::BadFormat) };
+        match self {
+            Rule::Range(min, max) => match integer(value) {
+                Some(n) => in_range(n, *min, *max),
+                None => Err(Problem::Unparseable),
+            },
+            // out of range if some unit fits the value but none in range
+            Rule::Units(units) => {
+                let mut result: Result<(), Problem> = Err(Problem::Unparseable);
+                for (unit, min, max) in units.iter() {
+                    if let Some(n) = value.strip_suffix(unit.as_str()).and_then(integer) {
+                        result = in_range(n, *min, *max);
+                        if result.is_ok() {
+                            break;
+                        }
+                    }
+                }
+                result
+            }
+            Rule::Regex(re) => ok_if(re.is_match(value)),
+            Rule::OneOf(values) => ok_if(values.iter().any(|v| v == value)),
+            Rule::Length(n) => ok_if(value.chars().count() == *n),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Field {
+    key: String,
+    optional: bool,
+    rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Schema {
+    fields: Vec<Field>,
+}
+
+// split the first whitespace-delimited word off the front of `s`
+fn word(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    match s.find(char::is_whitespace) {
+        Some(i) => (&s[..i], s[i..].trim()),
+        None => (s, ""),
+    }
+}
+
+fn int(s: &str, line: usize) -> Result<isize, SchemaError> {
+    s.parse()
+        .map_err(|_| SchemaError::new(line, "expected an integer"))
+}
+
+impl TryFrom<&str> for Schema {
+    type Error = SchemaError;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let mut fields: Vec<Field> = Vec::new();
+        for (i, l) in string.lines().enumerate() {
+            let line: usize = i + 1;
+            if l.trim().is_empty() || l.trim_start().starts_with('#') {
+                continue;
+            }
+            let (key, rest) = word(l);
+            let (name, args) = word(rest);
+            let position = match fields.iter().position(|f| f.key == key) {
+                Some(p) => p,
+                None => {
+                    fields.push(Field {
+                        key: key.to_string(),
+                        optional: false,
+                        rules: Vec::new(),
+                    });
+                    fields.len() - 1
+                }
+            };
+            let field = &mut fields[position];
+            let words: Vec<&str> = args.split_whitespace().collect();
+            let rule: Rule = match (name, &words[..]) {
+                ("optional", []) => {
+                    field.optional = true;
+                    continue;
+                }
+                ("range", [min, max]) => Rule::Range(int(min, line)?, int(max, line)?),
+                ("unit", [unit, min, max]) => {
+                    let unit = (unit.to_string(), int(min, line)?, int(max, line)?);
+                    if let Some(Rule::Units(units)) = field.rules.last_mut() {
+                        units.push(unit);
+                        continue;
+                    }
+                    Rule::Units(vec![unit])
+                }
+                ("regex", _) if !args.is_empty() => {
+                    // anchored, so the pattern has to match the whole value
+                    Rule::Regex(
+                        Regex::new(&format!("^(?:{})$", args))
+                            .map_err(|_| SchemaError::new(line, "bad regex"))?,
+                    )
+                }
+                ("one-of", _) if !words.is_empty() => {
+                    Rule::OneOf(words.iter().map(|w| w.to_string()).collect())
+                }
+                ("length", [n]) => Rule::Length(
+                    n.parse()
+                        .map_err(|_| SchemaError::new(line, "expected a length"))?,
+                ),
+                ("range", _)
+                | ("unit", _)
+                | ("regex", _)
+                | ("one-of", _)
+                | ("length", _)
+                | ("optional", _) => {
+                    return Err(SchemaError::new(line, "wrong arguments for rule"));
+                }
+                _ => return Err(SchemaError::new(line, "unknown rule")),
+            };
+            field.rules.push(rule);
+        }
+        Ok(Schema { fields })
+    }
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Schema::try_from(DEFAULT_SCHEMA).expect("default schema is valid")
+    }
+}
+
+impl Schema {
+    // in the order the schema first mentions them
+    pub fn keys(&self) -> Vec<&str> {
+        self.fields.iter().map(|f| f.key.as_str()).collect()
+    }
+
+    // part 1
+    pub fn is_complete(&self, passport: &Passport) -> bool {
+        self.fields
+            .iter()
+            .all(|f| f.optional || passport.0.contains_key(f.key.as_str()))
+    }
+
+    // part 2
+    pub fn is_valid(&self, passport: &Passport) -> bool {
+        self.violations(passport).is_empty()
+    }
+
+    pub fn violations(&self, passport: &Passport) -> Vec<Violation> {
+        let mut violations: Vec<Violation> = Vec::new();
+        for f in self.fields.iter() {
+            let value: &str = match passport.0.get(f.key.as_str()) {
+                Some(value) => value,
+                None => {
+                    if !f.optional {
+                        violations.push(Violation {
+                            key: f.key.clone(),
+                            rule: "required",
+                            problem: Problem::Missing,
+                            value: None,
+                        });
+                    }
+                    continue;
+                }
+            };
+            for r in f.rules.iter() {
+                if let Err(problem) = r.check(value) {
+                    violations.push(Violation {
+                        key: f.key.clone(),
+                        rule: r.name(),
+                        problem,
+                        value: Some(value.to_string()),
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_schema() {
+        let schema = Schema::default();
+        assert_eq!(schema.fields.len(), 8);
+        assert!(schema.fields.iter().any(|f| f.key == "cid" && f.optional));
+        let hgt = schema.fields.iter().find(|f| f.key == "hgt").unwrap();
+        assert_eq!(hgt.rules[0].check("150cm"), Ok(()));
+        assert_eq!(hgt.rules[0].check("76in"), Ok(()));
+        assert_eq!(hgt.rules[0].check("76cm"), Err(Problem::OutOfRange));
+        assert_eq!(hgt.rules[0].check("190"), Err(Problem::Unparseable));
+    }
+
+    // (field, value, valid): the puzzle's examples, then edge cases for each rule
+    const FIELD_CASES: &[(&str, &str, bool)] = &[
+        ("byr", "2002", true),
+        ("byr", "2003", false),
+        ("hgt", "60in", true),
+        ("hgt", "190cm", true),
+        ("hgt", "190in", false),
+        ("hgt", "190", false),
+        ("hcl", "#123abc", true),
+        ("hcl", "#123abz", false),
+        ("hcl", "123abc", false),
+        ("ecl", "brn", true),
+        ("ecl", "wat", false),
+        ("pid", "000000001", true),
+        ("pid", "0123456789", false),
+        ("byr", "1920", true),
+        ("byr", "1919", false),
+        ("byr", "+1950", false),
+        ("byr", "1950x", false),
+        ("byr", "", false),
+        ("iyr", "2010", true),
+        ("iyr", "2020", true),
+        ("iyr", "2009", false),
+        ("iyr", "2021", false),
+        ("eyr", "2020", true),
+        ("eyr", "2030", true),
+        ("eyr", "2019", false),
+        ("eyr", "2031", false),
+        ("hgt", "150cm", true),
+        ("hgt", "193cm", true),
+        ("hgt", "149cm", false),
+        ("hgt", "194cm", false),
+        ("hgt", "59in", true),
+        ("hgt", "76in", true),
+        ("hgt", "58in", false),
+        ("hgt", "77in", false),
+        ("hgt", "+60in", false),
+        ("hgt", "cm", false),
+        ("hgt", "170cmin", false),
+        ("hcl", "#123abcde", false),
+        ("hcl", "x#123abc", false),
+        ("hcl", "#123ABC", false),
+        ("hcl", "#12345", false),
+        ("ecl", "amb", true),
+        ("ecl", "oth", true),
+        ("ecl", "xamb", false),
+        ("ecl", "ambx", false),
+        ("ecl", "AMB", false),
+        ("pid", "12345678a", false),
+        ("pid", "+12345678", false),
+        ("pid", "12345678", false),
+        ("pid", "x123456789", false),
+        ("cid", "anything", true),
+    ];
+
+    #[test]
+    fn test_field_rules() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = Schema::default();
+        for &(key, value, valid) in FIELD_CASES.iter() {
+            let kv = format!("{}:{}", key, value);
+            let violations: Vec<Violation> = schema
+                .violations(&Passport::try_from(kv.as_str())?)
+                .into_iter()
+                .filter(|v| v.key == key)
+                .collect();
+            assert_eq!(violations.is_empty(), valid, "{} {:?}", kv, violations);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_errors() {
+        for (schema, line) in [
+            ("byr range 1920", 1),
+            ("byr range 1 2\n\nbyr ranged 1 2", 3),
+            ("# comment\nhcl regex (", 2),
+            ("ecl one-of", 1),
+            ("pid length nine", 1),
+        ]
+        .iter()
+        {
+            match Schema::try_from(*schema) {
+                Err(e) => assert_eq!(e.line, *line, "{}", e),
+                Ok(_) => panic!("{:?} should not parse", schema),
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_schema() -> Result<(), Box<dyn std::error::Error>> {
+        let schema =
+            Schema::try_from("ecl one-of red blue\nwgt unit kg 50 90\nwgt unit lb 110 200")?;
+        let passport = Passport::try_from("ecl:red wgt:180lb")?;
+        assert!(schema.is_valid(&passport));
+        let passport = Passport::try_from("ecl:blu wgt:180lb")?;
+        assert!(!schema.is_valid(&passport));
+        assert!(!schema.is_complete(&Passport::try_from("ecl:red")?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_violations() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = Schema::default();
+        let passport = Passport::try_from("byr:19x0 iyr:2009 hgt:190 hcl:123abc ecl:brn pid:1")?;
+        assert_eq!(
+            schema
+                .violations(&passport)
+                .into_iter()
+                .map(|v| (v.key, v.rule, v.problem))
+                .collect::<Vec<_>>(),
+            vec![
+                ("byr".to_string(), "range", Problem::Unparseable),
+                ("iyr".to_string(), "range", Problem::OutOfRange),
+                ("eyr".to_string(), "required", Problem::Missing),
+                ("hgt".to_string(), "unit", Problem::Unparseable),
+                ("hcl".to_string(), "regex", Problem::BadFormat),
+                ("pid".to_string(), "regex", Problem::BadFormat),
+            ]
+        );
+        Ok(())
+    }
+}
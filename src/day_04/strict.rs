@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+
+use super::schema::Schema;
+use super::Passport;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IssueKind {
+    Duplicate(String, (usize, usize)),
+    Unknown(String),
+    Malformed(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    pub line: usize,
+    pub column: usize,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            IssueKind::Duplicate(key, (line, column)) => write!(
+                f,
+                "duplicate key {} (first set at line {}, column {})",
+                key, line, column
+            ),
+            IssueKind::Unknown(key) => write!(f, "unknown key {}", key),
+            IssueKind::Malformed(token) => write!(f, "malformed token {:?}", token),
+        }
+    }
+}
+
+pub fn parse_strict<'a>(input: &'a str, schema: &Schema) -> Result<Vec<Passport<'a>>, Vec<Issue>> {
+    let token_regex = Regex::new(r"\S+").unwrap();
+    let known: Vec<&str> = schema.keys();
+    let mut passports: Vec<Passport> = Vec::new();
+    let mut issues: Vec<Issue> = Vec::new();
+    let mut current: HashMap<&str, &str> = HashMap::new();
+    let mut seen: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (i, l) in input.lines().chain(std::iter::once("")).enumerate() {
+        if l.trim().is_empty() {
+            if !current.is_empty() {
+                passports.push(Passport(current));
+                current = HashMap::new();
+                seen.clear();
+            }
+            continue;
+        }
+        for m in token_regex.find_iter(l) {
+            let position = (i + 1, l[..m.start()].chars().count() + 1);
+            let issue = |kind: IssueKind| Issue {
+                line: position.0,
+                column: position.1,
+                kind,
+            };
+            let (k, v) = match &m.as_str().split(':').collect::<Vec<&str>>()[..] {
+                &[k, v] if !k.is_empty() && !v.is_empty() => (k, v),
+                _ => {
+                    issues.push(issue(IssueKind::Malformed(m.as_str().to_string())));
+                    continue;
+                }
+            };
+            if !known.contains(&k) {
+                issues.push(issue(IssueKind::Unknown(k.to_string())));
+            }
+            if let Some(&first) = seen.get(k) {
+                issues.push(issue(IssueKind::Duplicate(k.to_string(), first)));
+                continue;
+            }
+            seen.insert(k, position);
+            current.insert(k, v);
+        }
+    }
+    if issues.is_empty() {
+        Ok(passports)
+    } else {
+        Err(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    #[test]
+    fn test_strict_example() {
+        let passports = parse_strict(TEST_INPUT, &Schema::default()).unwrap();
+        assert_eq!(passports.len(), 4);
+        assert_eq!(passports[1].0.get("cid"), Some(&"350"));
+    }
+
+    #[test]
+    fn test_strict_issues() {
+        let input = concat!(
+            "byr:1920 iyr:2010\n",
+            "  byr:2005 foo:bar\n",
+            "\n",
+            "byr:1920 eyr 2020:x hcl:#a:b ecl:\n",
+        );
+        let issues = parse_strict(input, &Schema::default()).err().unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                Issue {
+                    line: 2,
+                    column: 3,
+                    kind: IssueKind::Duplicate("byr".to_string(), (1, 1)),
+                },
+                Issue {
+                    line: 2,
+                    column: 12,
+                    kind: IssueKind::Unknown("foo".to_string()),
+                },
+                Issue {
+                    line: 4,
+                    column: 10,
+                    kind: IssueKind::Malformed("eyr".to_string()),
+                },
+                Issue {
+                    line: 4,
+                    column: 14,
+                    kind: IssueKind::Unknown("2020".to_string()),
+                },
+                Issue {
+                    line: 4,
+                    column: 21,
+                    kind: IssueKind::Malformed("hcl:#a:b".to_string()),
+                },
+                Issue {
+                    line: 4,
+                    column: 30,
+                    kind: IssueKind::Malformed("ecl:".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "line 2, column 3: duplicate key byr (first set at line 1, column 1)"
+        );
+    }
+}
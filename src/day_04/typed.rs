@@ -0,0 +1,364 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::schema::{Problem, Schema, Violation};
+use super::Passport;
+
+const CM_PER_INCH: f64 = 2.54;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Unit {
+    Centimeters,
+    Inches,
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unit::Centimeters => write!(f, "cm"),
+            Unit::Inches => write!(f, "in"),
+        }
+    }
+}
+
+// kept in centimetres, remembering the unit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Height {
+    cm: f64,
+    unit: Unit,
+}
+
+impl Height {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        let cm: f64 = match unit {
+            Unit::Centimeters => value,
+            Unit::Inches => value * CM_PER_INCH,
+        };
+        Height { cm, unit }
+    }
+
+    pub fn get(&self, unit: Unit) -> f64 {
+        match unit {
+            Unit::Centimeters => self.cm,
+            Unit::Inches => self.cm / CM_PER_INCH,
+        }
+    }
+}
+
+impl TryFrom<&str> for Height {
+    type Error = Problem;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let (value, unit) = if let Some(v) = string.strip_suffix("cm") {
+            (v, Unit::Centimeters)
+        } else if let Some(v) = string.strip_suffix("in") {
+            (v, Unit::Inches)
+        } else {
+            return Err(Problem::Unparseable);
+        };
+        if !value.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Problem::Unparseable);
+        }
+        let value: u32 = value.parse().map_err(|_| Problem::Unparseable)?;
+        Ok(Height::new(value as f64, unit))
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.get(self.unit).round(), self.unit)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HairColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl TryFrom<&str> for HairColor {
+    type Error = Problem;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let hex = string.strip_prefix('#').ok_or(Problem::Unparseable)?;
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Problem::Unparseable);
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+        Ok(HairColor {
+            r: channel(0).map_err(|_| Problem::Unparseable)?,
+            g: channel(2).map_err(|_| Problem::Unparseable)?,
+            b: channel(4).map_err(|_| Problem::Unparseable)?,
+        })
+    }
+}
+
+impl fmt::Display for HairColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EyeColor {
+    Amber,
+    Blue,
+    Brown,
+    Gray,
+    Green,
+    Hazel,
+    Other,
+}
+
+impl TryFrom<&str> for EyeColor {
+    type Error = Problem;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        match string {
+            "amb" => Ok(EyeColor::Amber),
+            "blu" => Ok(EyeColor::Blue),
+            "brn" => Ok(EyeColor::Brown),
+            "gry" => Ok(EyeColor::Gray),
+            "grn" => Ok(EyeColor::Green),
+            "hzl" => Ok(EyeColor::Hazel),
+            "oth" => Ok(EyeColor::Other),
+            _ => Err(Problem::Unparseable),
+        }
+    }
+}
+
+impl fmt::Display for EyeColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            EyeColor::Amber => "amb",
+            EyeColor::Blue => "blu",
+            EyeColor::Brown => "brn",
+            EyeColor::Gray => "gry",
+            EyeColor::Green => "grn",
+            EyeColor::Hazel => "hzl",
+            EyeColor::Other => "oth",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+// kept as text so leading zeros survive
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassportId(String);
+
+impl TryFrom<&str> for PassportId {
+    type Error = Problem;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        if string.is_empty() || !string.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Problem::Unparseable);
+        }
+        Ok(PassportId(string.to_string()))
+    }
+}
+
+impl fmt::Display for PassportId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn year(string: &str) -> Result<u32, Problem> {
+    if !string.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Problem::Unparseable);
+    }
+    string.parse().map_err(|_| Problem::Unparseable)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidatedPassport {
+    pub birth_year: u32,
+    pub issue_year: u32,
+    pub expiration_year: u32,
+    pub height: Height,
+    pub hair_color: HairColor,
+    pub eye_color: EyeColor,
+    pub passport_id: PassportId,
+    pub country_id: Option<String>,
+}
+
+impl ValidatedPassport {
+    pub fn new(passport: &Passport, schema: &Schema) -> Result<Self, Vec<Violation>> {
+        let mut violations: Vec<Violation> = Vec::new();
+        let mut field = |key: &str| -> &str {
+            match passport.0.get(key) {
+                Some(value) => value,
+                None => {
+                    violations.push(Violation {
+                        key: key.to_string(),
+                        rule: "type",
+                        problem: Problem::Missing,
+                        value: None,
+                    });
+                    ""
+                }
+            }
+        };
+        let raw: [&str; 7] = [
+            field("byr"),
+            field("iyr"),
+            field("eyr"),
+            field("hgt"),
+            field("hcl"),
+            field("ecl"),
+            field("pid"),
+        ];
+        if !violations.is_empty() {
+            return Err(violations);
+        }
+        // record a parse failure and carry on, so every bad field is reported
+        fn parsed<T>(
+            violations: &mut Vec<Violation>,
+            key: &str,
+            value: &str,
+            result: Result<T, Problem>,
+        ) -> Option<T> {
+            match result {
+                Ok(t) => Some(t),
+                Err(problem) => {
+                    violations.push(Violation {
+                        key: key.to_string(),
+                        rule: "type",
+                        problem,
+                        value: Some(value.to_string()),
+                    });
+                    None
+                }
+            }
+        }
+        let v = &mut violations;
+        let birth_year = parsed(v, "byr", raw[0], year(raw[0]));
+        let issue_year = parsed(v, "iyr", raw[1], year(raw[1]));
+        let expiration_year = parsed(v, "eyr", raw[2], year(raw[2]));
+        let height = parsed(v, "hgt", raw[3], Height::try_from(raw[3]));
+        let hair_color = parsed(v, "hcl", raw[4], HairColor::try_from(raw[4]));
+        let eye_color = parsed(v, "ecl", raw[5], EyeColor::try_from(raw[5]));
+        let passport_id = parsed(v, "pid", raw[6], PassportId::try_from(raw[6]));
+        match (
+            birth_year,
+            issue_year,
+            expiration_year,
+            height,
+            hair_color,
+            eye_color,
+            passport_id,
+        ) {
+            (Some(byr), Some(iyr), Some(eyr), Some(hgt), Some(hcl), Some(ecl), Some(pid)) => {
+                let violations = schema.violations(passport);
+                if !violations.is_empty() {
+                    return Err(violations);
+                }
+                Ok(ValidatedPassport {
+                    birth_year: byr,
+                    issue_year: iyr,
+                    expiration_year: eyr,
+                    height: hgt,
+                    hair_color: hcl,
+                    eye_color: ecl,
+                    passport_id: pid,
+                    country_id: passport.0.get("cid").map(|s| s.to_string()),
+                })
+            }
+            _ => Err(violations),
+        }
+    }
+}
+
+impl fmt::Display for ValidatedPassport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "byr {}, iyr {}, eyr {}, hgt {} ({:.1}cm / {:.1}in), hcl {} ({}, {}, {}), ecl {}, pid {}",
+            self.birth_year,
+            self.issue_year,
+            self.expiration_year,
+            self.height,
+            self.height.get(Unit::Centimeters),
+            self.height.get(Unit::Inches),
+            self.hair_color,
+            self.hair_color.r,
+            self.hair_color.g,
+            self.hair_color.b,
+            self.eye_color,
+            self.passport_id
+        )?;
+        if let Some(cid) = &self.country_id {
+            write!(f, ", cid {}", cid)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_height_conversion() -> Result<(), Problem> {
+        let h = Height::try_from("72in")?;
+        assert!((h.get(Unit::Centimeters) - 182.88).abs() < 1e-9);
+        assert!((h.get(Unit::Inches) - 72.0).abs() < 1e-9);
+        assert_eq!(h.to_string(), "72in");
+        let h = Height::new(182.88, Unit::Centimeters);
+        assert!((h.get(Unit::Inches) - 72.0).abs() < 1e-9);
+        assert_eq!(Height::try_from("190"), Err(Problem::Unparseable));
+        assert_eq!(Height::try_from("+5cm"), Err(Problem::Unparseable));
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_fields() {
+        assert_eq!(
+            HairColor::try_from("#ff8000"),
+            Ok(HairColor {
+                r: 255,
+                g: 128,
+                b: 0
+            })
+        );
+        assert_eq!(HairColor::try_from("#ff800"), Err(Problem::Unparseable));
+        assert_eq!(EyeColor::try_from("hzl"), Ok(EyeColor::Hazel));
+        assert_eq!(EyeColor::try_from("wat"), Err(Problem::Unparseable));
+        assert_eq!(
+            PassportId::try_from("000000001").map(|p| p.to_string()),
+            Ok("000000001".to_string())
+        );
+        assert_eq!(PassportId::try_from("12a"), Err(Problem::Unparseable));
+    }
+
+    #[test]
+    fn test_pipeline() -> Result<(), Box<dyn std::error::Error>> {
+        let schema = Schema::default();
+        let p = Passport::try_from(
+            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f",
+        )?;
+        let v = ValidatedPassport::new(&p, &schema).map_err(|_| "should validate")?;
+        assert_eq!(v.birth_year, 1980);
+        assert_eq!(v.eye_color, EyeColor::Green);
+        assert_eq!(v.country_id, None);
+        // parses, but fails validation
+        let p = Passport::try_from(
+            "pid:087499704 hgt:74cm ecl:grn iyr:2012 eyr:2030 byr:1980 hcl:#623a2f",
+        )?;
+        let e = ValidatedPassport::new(&p, &schema).unwrap_err();
+        assert_eq!((e[0].rule, e[0].problem), ("unit", Problem::OutOfRange));
+        // fails to parse, so never reaches validation
+        let p = Passport::try_from(
+            "pid:087499704 hgt:74 ecl:wat iyr:2012 eyr:2030 byr:1980 hcl:#623a2f",
+        )?;
+        let e = ValidatedPassport::new(&p, &schema).unwrap_err();
+        assert_eq!(
+            e.iter()
+                .map(|v| (v.key.as_str(), v.rule))
+                .collect::<Vec<_>>(),
+            vec![("hgt", "type"), ("ecl", "type")]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use std::convert::TryFrom;
+use std::ops::Range;
+
+use super::{PassError, Seat};
+use crate::errors::AOCError;
+
+// seat IDs are `row * row_factor + column`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AircraftLayout {
+    row_bits: u32,
+    column_bits: u32,
+    excluded_front: usize,
+    excluded_back: usize,
+    row_factor: usize,
+}
+
+impl Default for AircraftLayout {
+    fn default() -> Self {
+        AircraftLayout {
+            row_bits: 7,
+            column_bits: 3,
+            excluded_front: 16,
+            excluded_back: 16,
+            row_factor: 8,
+        }
+    }
+}
+
+impl AircraftLayout {
+    pub fn new(
+        row_bits: u32,
+        column_bits: u32,
+        excluded_front: usize,
+        excluded_back: usize,
+        row_factor: usize,
+    ) -> Result<Self, AOCError<'static>> {
+        match row_bits.checked_add(column_bits) {
+            Some(1..=32) => {}
+            _ => return Err(AOCError::new("layout needs between 1 and 32 bits")),
+        }
+        if row_factor < 1 << column_bits {
+            return Err(AOCError::new(
+                "row factor is smaller than the number of columns",
+            ));
+        }
+        match excluded_front.checked_add(excluded_back) {
+            Some(excluded) if excluded <= 1 << row_bits => {}
+            _ => return Err(AOCError::new("layout excludes more rows than it has")),
+        }
+        // one past the largest seat ID has to fit, for `id_range`
+        let last_row: usize = (1 << row_bits) - 1;
+        if last_row
+            .checked_mul(row_factor)
+            .and_then(|id| id.checked_add(1 << column_bits))
+            .is_none()
+        {
+            return Err(AOCError::new("row factor is too large for seat IDs"));
+        }
+        Ok(AircraftLayout {
+            row_bits,
+            column_bits,
+            excluded_front,
+            excluded_back,
+            row_factor,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        1 << self.row_bits
+    }
+
+    pub fn columns(&self) -> usize {
+        1 << self.column_bits
+    }
+
+    pub fn seated_rows(&self) -> Range<usize> {
+        self.excluded_front..self.rows() - self.excluded_back
+    }
+
+    pub fn id(&self, row: usize, column: usize) -> usize {
+        row * self.row_factor + column
+    }
+
+    // where part 2's missing seat can be
+    pub fn id_range(&self) -> Range<usize> {
+        let rows = self.seated_rows();
+        if rows.is_empty() {
+            return 0..0;
+        }
+        self.id(rows.start, 0)..self.id(rows.end - 1, self.columns() - 1) + 1
+    }
+
+    pub fn seat(&self, row: usize, column: usize) -> Result<Seat, PassError> {
+        if row >= self.rows() || column >= self.columns() {
+            // only seats in the layout are sure to have an ID which fits
+            let id: usize = row.saturating_mul(self.row_factor).saturating_add(column);
+            return Err(PassError::OutOfRange(id));
+        }
+        Ok(Seat {
+            row,
+            column,
+            id: self.id(row, column),
+        })
+    }
+
+    pub fn seat_from_id(&self, id: usize) -> Result<Seat, PassError> {
+        self.seat(id / self.row_factor, id % self.row_factor)
+            .map_err(|_| PassError::OutOfRange(id))
+    }
+
+    pub fn encode(&self, seat: Seat) -> String {
+        let (row, column) = (seat.row(), seat.column());
+        let mut pass = String::new();
+        for i in (0..self.row_bits).rev() {
+            pass.push(if row >> i & 1 == 1 { 'B' } else { 'F' });
+        }
+        for i in (0..self.column_bits).rev() {
+            pass.push(if column >> i & 1 == 1 { 'R' } else { 'L' });
+        }
+        pass
+    }
+
+    pub fn decode(&self, pass: &str) -> Result<Seat, PassError> {
+        let expected: usize = (self.row_bits + self.column_bits) as usize;
+        let n: usize = pass.chars().count();
+        if n != expected {
+            return Err(PassError::Length(n, expected));
+        }
+        let (mut row, mut column): (usize, usize) = (0, 0);
+        for (position, c) in pass.chars().enumerate() {
+            match (position < self.row_bits as usize, c) {
+                (true, 'F') => row <<= 1,
+                (true, 'B') => row = row << 1 | 1,
+                (false, 'L') => column <<= 1,
+                (false, 'R') => column = column << 1 | 1,
+                _ => return Err(PassError::Character(position, c)),
+            }
+        }
+        Ok(Seat {
+            row,
+            column,
+            id: self.id(row, column),
+        })
+    }
+}
+
+// `<row bits>:<column bits>:<excluded front>:<excluded back>[:<row factor>]`
+impl TryFrom<&str> for AircraftLayout {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let parts: Vec<&str> = string.split(':').collect();
+        if parts.len() != 4 && parts.len() != 5 {
+            return Err(AOCError::new(
+                "expected layout <row bits>:<column bits>:<front>:<back>[:<row factor>]",
+            )
+            .into());
+        }
+        let column_bits: u32 = parts[1].parse()?;
+        let row_factor: usize = match parts.get(4) {
+            Some(f) => f.parse()?,
+            None => 1 << column_bits.min(31),
+        };
+        Ok(AircraftLayout::new(
+            parts[0].parse()?,
+            column_bits,
+            parts[2].parse()?,
+            parts[3].parse()?,
+            row_factor,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_layout() {
+        let layout = AircraftLayout::default();
+        assert_eq!(layout.id_range(), 128..896);
+        assert_eq!(layout.seated_rows(), 16..112);
+        let seat = layout.decode("FBFBBFFRLR").unwrap();
+        assert_eq!((seat.row(), seat.column(), seat.id()), (44, 5, 357));
+    }
+
+    #[test]
+    fn test_other_layouts() -> Result<(), Box<dyn std::error::Error>> {
+        // 16 rows of 4 seats, with IDs numbered as if rows had 10
+        let layout = AircraftLayout::try_from("4:2:1:2:10")?;
+        assert_eq!(layout.id_range(), 10..134);
+        let seat = layout.decode("BFFBLR")?;
+        assert_eq!((seat.row(), seat.column(), seat.id()), (9, 1, 91));
+        assert_eq!(layout.encode(seat), "BFFBLR");
+        assert_eq!(layout.seat_from_id(94), Err(PassError::OutOfRange(94)));
+        assert_eq!(layout.decode("BFFBL"), Err(PassError::Length(5, 6)));
+        for row in 0..layout.rows() {
+            for column in 0..layout.columns() {
+                let seat = layout.seat(row, column)?;
+                assert_eq!(layout.decode(&layout.encode(seat))?, seat);
+            }
+        }
+        assert!(AircraftLayout::try_from("4:2:1:2:3").is_err());
+        assert!(AircraftLayout::try_from("2:2:3:2").is_err());
+        assert!(AircraftLayout::try_from("7:3").is_err());
+        assert!(AircraftLayout::try_from("4294967295:1:0:0").is_err());
+        assert!(AircraftLayout::try_from("1:0:0:0:18446744073709551615").is_err());
+        assert!(AircraftLayout::try_from("1:0:18446744073709551615:1").is_err());
+        assert!(layout.seat(usize::MAX, 0).is_err());
+        Ok(())
+    }
+}
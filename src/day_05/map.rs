@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+
+use super::layout::AircraftLayout;
+use super::PassError;
+
+const OCCUPIED: char = '#';
+const FREE: char = '.';
+// a seat in one of the rows the layout says don't exist
+const MISSING: char = ' ';
+
+pub struct SeatMap {
+    layout: AircraftLayout,
+    occupied: HashSet<usize>,
+}
+
+impl SeatMap {
+    pub fn new(layout: AircraftLayout, input: &str) -> Result<Self, PassError> {
+        let mut occupied: HashSet<usize> = HashSet::new();
+        for l in input.lines() {
+            occupied.insert(layout.decode(l)?.id());
+        }
+        Ok(SeatMap { layout, occupied })
+    }
+
+    fn status(&self, row: usize, column: usize) -> char {
+        if self.occupied.contains(&self.layout.id(row, column)) {
+            OCCUPIED
+        } else if self.layout.seated_rows().contains(&row) {
+            FREE
+        } else {
+            MISSING
+        }
+    }
+
+    pub fn occupancy(&self) -> Vec<usize> {
+        (0..self.layout.rows())
+            .map(|row| {
+                (0..self.layout.columns())
+                    .filter(|&column| self.status(row, column) == OCCUPIED)
+                    .count()
+            })
+            .collect()
+    }
+
+    pub fn gaps(&self) -> Vec<Range<usize>> {
+        let mut gaps: Vec<Range<usize>> = Vec::new();
+        for row in self.layout.seated_rows() {
+            for column in 0..self.layout.columns() {
+                if self.status(row, column) != FREE {
+                    continue;
+                }
+                let id: usize = self.layout.id(row, column);
+                match gaps.last_mut() {
+                    Some(gap) if gap.end == id => gap.end += 1,
+                    _ => gaps.push(id..id + 1),
+                }
+            }
+        }
+        gaps
+    }
+}
+
+// `#` occupied, `.` free, blank for rows which don't exist
+impl fmt::Display for SeatMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width: usize = (self.layout.rows() - 1).to_string().len();
+        for (row, count) in self.occupancy().into_iter().enumerate() {
+            let seats: String = (0..self.layout.columns())
+                .map(|column| self.status(row, column))
+                .collect();
+            writeln!(
+                f,
+                "{:>w$} {} {}/{}",
+                row,
+                seats,
+                count,
+                self.layout.columns(),
+                w = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+
+    #[test]
+    fn test_small_plane() -> Result<(), Box<dyn std::error::Error>> {
+        // 4 rows of 2 seats with the first missing, and a pass for it anyway
+        let layout = AircraftLayout::try_from("2:1:1:0")?;
+        let map = SeatMap::new(layout, "FFR\nFBL\nBBR\n")?;
+        assert_eq!(map.occupancy(), vec![1, 1, 0, 1]);
+        assert_eq!(map.gaps(), vec![3..7]);
+        assert_eq!(map.to_string(), "0  # 1/2\n1 #. 1/2\n2 .. 0/2\n3 .# 1/2\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_gaps_with_row_factor() -> Result<(), Box<dyn std::error::Error>> {
+        // IDs jump by 10 between rows, so no run crosses a row boundary
+        let layout = AircraftLayout::try_from("2:1:0:0:10")?;
+        let map = SeatMap::new(layout, "FFL\nFBL\nBFR\n")?;
+        assert_eq!(map.gaps(), vec![1..2, 11..12, 20..21, 30..32]);
+        Ok(())
+    }
+}
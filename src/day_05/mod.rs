@@ -1,48 +1,217 @@
 use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
 
 use crate::errors::AOCError;
 
-struct Seat(usize);
+mod layout;
+mod map;
 
-impl From<&str> for Seat {
-    fn from(string: &str) -> Self {
-        let mut i: usize = 0;
-        let mut e: u32 = 0;
-        for c in string.chars().rev() {
-            if c == 'B' || c == 'R' {
-                i += (2 as usize).pow(e);
+use layout::AircraftLayout;
+use map::SeatMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PassError {
+    // found, expected
+    Length(usize, usize),
+    // 0-indexed position
+    Character(usize, char),
+    OutOfRange(usize),
+}
+
+impl fmt::Display for PassError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PassError::Length(n, expected) => write!(
+                f,
+                "boarding pass has {} characters, expected {}",
+                n, expected
+            ),
+            PassError::Character(i, c) => {
+                write!(f, "unexpected character {:?} at position {}", c, i)
             }
-            e += 1
+            PassError::OutOfRange(id) => write!(f, "no seat has ID {}", id),
         }
-        Seat(i)
+    }
+}
+
+impl Error for PassError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Seat {
+    row: usize,
+    column: usize,
+    id: usize,
+}
+
+impl Seat {
+    fn row(&self) -> usize {
+        self.row
+    }
+
+    fn column(&self) -> usize {
+        self.column
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "row {}, column {}, seat ID {}",
+            self.row, self.column, self.id
+        )
     }
 }
 
 pub fn part_1(input: String) -> Result<String, Box<dyn std::error::Error>> {
-    input
-        .lines()
-        .map(|l| Seat::from(l).0)
-        .max()
-        .ok_or("no input".into())
-        .map(|i| i.to_string())
+    let layout = AircraftLayout::default();
+    let mut max: Option<usize> = None;
+    for l in input.lines() {
+        max = max.max(Some(layout.decode(l)?.id()));
+    }
+    max.ok_or("no input".into()).map(|i| i.to_string())
 }
 
 pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
+    missing_seat(&input, &AircraftLayout::default())
+}
+
+// the first seat in the seated rows without a boarding pass
+fn missing_seat(input: &str, layout: &AircraftLayout) -> Result<String, Box<dyn Error>> {
     let mut seats: HashSet<usize> = HashSet::new();
     for l in input.lines() {
-        let s = Seat::from(l);
-        if (128..896).contains(&s.0) {
-            seats.insert(s.0);
-        }
+        seats.insert(layout.decode(l)?.id());
     }
-    for i in 128..896 {
-        if !seats.contains(&i) {
-            return Ok(i.to_string());
+    for id in layout.id_range() {
+        // with a row factor above the column count, some IDs aren't seats
+        if layout.seat_from_id(id).is_ok() && !seats.contains(&id) {
+            return Ok(id.to_string());
         }
     }
     Err(Box::new(AOCError::new("no answer")))
 }
 
+// a layout given as the last argument, in the format `AircraftLayout` parses
+fn layout_from(args: &[String]) -> Result<(AircraftLayout, &[String]), Box<dyn Error>> {
+    match args.split_last() {
+        Some((last, rest)) if last.contains(':') => {
+            Ok((AircraftLayout::try_from(last.as_str())?, rest))
+        }
+        _ => Ok((AircraftLayout::default(), args)),
+    }
+}
+
+fn encode(args: &[String]) -> Result<String, Box<dyn Error>> {
+    let (layout, args) = layout_from(args)?;
+    let first: usize = args
+        .first()
+        .ok_or(AOCError::new("missing argument for seat ID or row"))?
+        .parse()?;
+    let seat: Seat = match args.get(1) {
+        Some(column) => layout.seat(first, column.parse()?)?,
+        None => layout.seat_from_id(first)?,
+    };
+    Ok(layout.encode(seat))
+}
+
+fn decode(args: &[String]) -> Result<String, Box<dyn Error>> {
+    let (layout, args) = layout_from(args)?;
+    let pass: &String = args
+        .first()
+        .ok_or(AOCError::new("missing argument for boarding pass"))?;
+    Ok(layout.decode(pass)?.id().to_string())
+}
+
+fn map(args: &[String], input: String) -> Result<String, Box<dyn Error>> {
+    let (layout, _) = layout_from(args)?;
+    Ok(SeatMap::new(layout, &input)?
+        .to_string()
+        .trim_end()
+        .to_string())
+}
+
+fn gaps(args: &[String], input: String) -> Result<String, Box<dyn Error>> {
+    let (layout, _) = layout_from(args)?;
+    Ok(SeatMap::new(layout, &input)?
+        .gaps()
+        .iter()
+        .map(|gap| match gap.len() {
+            1 => gap.start.to_string(),
+            _ => format!("{}-{}", gap.start, gap.end - 1),
+        })
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+fn list(args: &[String], input: String) -> Result<String, Box<dyn Error>> {
+    let (layout, _) = layout_from(args)?;
+    let mut passes: Vec<(Seat, usize, &str)> = Vec::new();
+    for (i, l) in input.lines().enumerate() {
+        passes.push((layout.decode(l)?, i + 1, l));
+    }
+    passes.sort_by_key(|(seat, line, _)| (seat.id(), *line));
+    let mut lines: Vec<String> = Vec::new();
+    let mut duplicates: usize = 0;
+    // the earliest line with the current seat ID
+    let mut first: usize = 0;
+    for (i, (seat, line, pass)) in passes.iter().enumerate() {
+        let mut entry = format!("{} (line {}): {}", pass, line, seat);
+        if i > 0 && passes[i - 1].0 == *seat {
+            duplicates += 1;
+            entry.push_str(&format!(", duplicate of line {}", first));
+        } else {
+            first = *line;
+        }
+        lines.push(entry);
+    }
+    lines.push(format!(
+        "{} pass{}, {} duplicate{}",
+        passes.len(),
+        if passes.len() == 1 { "" } else { "es" },
+        duplicates,
+        if duplicates == 1 { "" } else { "s" }
+    ));
+    Ok(lines.join("\n"))
+}
+
+fn missing(args: &[String], input: String) -> Result<String, Box<dyn Error>> {
+    let (layout, _) = layout_from(args)?;
+    missing_seat(&input, &layout)
+}
+
+// commands: `encode <seat ID> [<layout>]` or `encode <row> <column> [<layout>]`
+//           `decode <boarding pass> [<layout>]`
+//           `map [<layout>]`
+//           `gaps [<layout>]`
+//           `list [<layout>]`
+//           `missing [<layout>]`
+pub fn command(
+    name: &str,
+    args: &[String],
+    input: Option<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let input = input.ok_or(AOCError::new("input file missing"));
+    match name {
+        "decode" => decode(args),
+        "encode" => encode(args),
+        "gaps" => gaps(args, input?),
+        "list" => list(args, input?),
+        "map" => map(args, input?),
+        "missing" => missing(args, input?),
+        _ => Err(AOCError::new("unknown command").into()),
+    }
+}
+
 pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
     Ok(format!(
         "{}\n{}",
@@ -57,8 +226,99 @@ mod tests {
 
     #[test]
     fn test_part_1_example() -> Result<(), Box<dyn std::error::Error>> {
-        assert_eq!(Seat::from("FBFBBFFRLR").0, 357);
-        assert_eq!(Seat::from("BFFFBBFRRR").0, 567);
+        let layout = AircraftLayout::default();
+        assert_eq!(layout.decode("FBFBBFFRLR")?.id(), 357);
+        assert_eq!(layout.decode("BFFFBBFRRR")?.id(), 567);
+        assert_eq!(layout.decode("FFFBBBFRRR")?.id(), 119);
+        assert_eq!(layout.decode("BBFFBBFRLL")?.id(), 820);
+        Ok(())
+    }
+
+    #[test]
+    fn test_seat_accessors() -> Result<(), Box<dyn std::error::Error>> {
+        let seat = AircraftLayout::default().decode("BFFFBBFRRR")?;
+        assert_eq!((seat.row(), seat.column(), seat.id()), (70, 7, 567));
+        assert_eq!(seat.to_string(), "row 70, column 7, seat ID 567");
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_duplicates() -> Result<(), Box<dyn std::error::Error>> {
+        let input = "BBFFBBFRLL\nFBFBBFFRLR\nBBFFBBFRLL\n".to_string();
+        assert_eq!(
+            list(&[], input)?,
+            concat!(
+                "FBFBBFFRLR (line 2): row 44, column 5, seat ID 357\n",
+                "BBFFBBFRLL (line 1): row 102, column 4, seat ID 820\n",
+                "BBFFBBFRLL (line 3): row 102, column 4, seat ID 820, duplicate of line 1\n",
+                "3 passes, 1 duplicate",
+            )
+        );
+        let input = "BBFFBBFRLL\nBBFFBBFRLL\nBBFFBBFRLL\n".to_string();
+        assert_eq!(
+            list(&[], input)?,
+            concat!(
+                "BBFFBBFRLL (line 1): row 102, column 4, seat ID 820\n",
+                "BBFFBBFRLL (line 2): row 102, column 4, seat ID 820, duplicate of line 1\n",
+                "BBFFBBFRLL (line 3): row 102, column 4, seat ID 820, duplicate of line 1\n",
+                "3 passes, 2 duplicates",
+            )
+        );
+        assert_eq!(
+            list(&[], "FBFBBFFRLR\n".to_string())?.lines().last(),
+            Some("1 pass, 0 duplicates")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_decoding() {
+        let layout = AircraftLayout::default();
+        assert_eq!(layout.decode("FBFBBFFRL"), Err(PassError::Length(9, 10)));
+        assert_eq!(layout.decode(""), Err(PassError::Length(0, 10)));
+        assert_eq!(
+            layout.decode("FBFBBFFRLX"),
+            Err(PassError::Character(9, 'X'))
+        );
+        // letters in the wrong half of the pass
+        assert_eq!(
+            layout.decode("FBFBBFRRLR"),
+            Err(PassError::Character(6, 'R'))
+        );
+        assert_eq!(
+            layout.decode("FBFBBFFRBR"),
+            Err(PassError::Character(8, 'B'))
+        );
+    }
+
+    #[test]
+    fn test_encoding() -> Result<(), Box<dyn std::error::Error>> {
+        let layout = AircraftLayout::default();
+        assert_eq!(layout.encode(layout.seat_from_id(357)?), "FBFBBFFRLR");
+        assert_eq!(layout.encode(layout.seat(70, 7)?), "BFFFBBFRRR");
+        assert_eq!(layout.seat_from_id(1024), Err(PassError::OutOfRange(1024)));
+        assert!(layout.seat(128, 0).is_err());
+        assert!(layout.seat(0, 8).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let layout = AircraftLayout::default();
+        for id in 0..1024 {
+            let seat = layout.seat_from_id(id)?;
+            assert_eq!(layout.decode(&layout.encode(seat))?, seat);
+            assert_eq!(layout.seat(id >> 3, id & 0b111)?, seat);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_seat_in_small_plane() -> Result<(), Box<dyn std::error::Error>> {
+        // 4 rows of 2 seats, the first row missing, and row 2 column 1 free
+        let layout = AircraftLayout::try_from("2:1:1:0")?;
+        let input = "FBL\nFBR\nBFL\nBBL\nBBR\n";
+        assert_eq!(missing_seat(input, &layout)?, "5");
         Ok(())
     }
 }
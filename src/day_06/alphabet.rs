@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use bitvec::prelude::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::errors::AOCError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnswerError {
+    line: usize,
+    column: usize,
+    symbol: String,
+}
+
+impl AnswerError {
+    pub fn new(line: usize, column: usize, symbol: String) -> Self {
+        AnswerError {
+            line,
+            column,
+            symbol,
+        }
+    }
+}
+
+impl fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {:?} is not in the alphabet",
+            self.line, self.column, self.symbol
+        )
+    }
+}
+
+impl Error for AnswerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Alphabet {
+    symbols: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::try_from("lowercase").unwrap()
+    }
+}
+
+// `lowercase`, `letters`, `alphanumeric`, or the symbols themselves
+impl TryFrom<&str> for Alphabet {
+    type Error = AOCError<'static>;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let lowercase = ('a'..='z').map(|c| c.to_string());
+        let uppercase = ('A'..='Z').map(|c| c.to_string());
+        let digits = ('0'..='9').map(|c| c.to_string());
+        let symbols: Vec<String> = match string {
+            "lowercase" => lowercase.collect(),
+            "letters" => lowercase.chain(uppercase).collect(),
+            "alphanumeric" => lowercase.chain(uppercase).chain(digits).collect(),
+            _ => string.graphemes(true).map(|g| g.to_string()).collect(),
+        };
+        if symbols.is_empty() {
+            return Err(AOCError::new("alphabet is empty"));
+        }
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for (i, s) in symbols.iter().enumerate() {
+            if s.trim().is_empty() {
+                return Err(AOCError::new("alphabet contains whitespace"));
+            }
+            if index.insert(s.clone(), i).is_some() {
+                return Err(AOCError::new("alphabet repeats a symbol"));
+            }
+        }
+        Ok(Alphabet { symbols, index })
+    }
+}
+
+impl Alphabet {
+    pub fn symbols(&self) -> &[String] {
+        &self.symbols
+    }
+
+    pub fn empty(&self) -> BitVec {
+        bitvec![0; self.symbols.len()]
+    }
+
+    pub fn full(&self) -> BitVec {
+        bitvec![1; self.symbols.len()]
+    }
+
+    // whitespace is ignored
+    pub fn person(&self, line: usize, answers: &str) -> Result<BitVec, AnswerError> {
+        let mut bits = self.empty();
+        for (column, g) in answers.graphemes(true).enumerate() {
+            if g.trim().is_empty() {
+                continue;
+            }
+            match self.index.get(g) {
+                Some(&i) => bits.set(i, true),
+                None => return Err(AnswerError::new(line, column + 1, g.to_string())),
+            }
+        }
+        Ok(bits)
+    }
+
+    pub fn group(&self, first_line: usize, group: &str) -> Result<Vec<BitVec>, AnswerError> {
+        group
+            .lines()
+            .enumerate()
+            .map(|(i, person)| self.person(first_line + i, person))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alphabets() -> Result<(), Box<dyn std::error::Error>> {
+        let letters = Alphabet::try_from("letters")?;
+        let bits = letters.person(1, "aZ")?;
+        assert_eq!(bits.len(), 52);
+        assert_eq!(bits.iter_ones().collect::<Vec<usize>>(), vec![0, 51]);
+        let cards = Alphabet::try_from("♠♥♦♣")?;
+        assert_eq!(cards.person(1, "♣ ♠")?, bits![1, 0, 0, 1]);
+        let flags = Alphabet::try_from("🇫🇷🇩🇪")?;
+        assert_eq!(flags.symbols().len(), 2);
+        assert!(Alphabet::try_from("abca").is_err());
+        assert!(Alphabet::try_from("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_alphabet() {
+        let alphabet = Alphabet::default();
+        let e = alphabet.group(4, "ab\naB").unwrap_err();
+        assert_eq!(
+            e,
+            AnswerError {
+                line: 5,
+                column: 2,
+                symbol: "B".to_string()
+            }
+        );
+        assert_eq!(
+            e.to_string(),
+            "line 5, column 2: \"B\" is not in the alphabet"
+        );
+        assert!(alphabet.person(1, "a1").is_err());
+    }
+}
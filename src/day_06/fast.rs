@@ -0,0 +1,125 @@
+use super::alphabet::AnswerError;
+
+// close a group, adding its union and intersection to the totals
+fn finish(totals: &mut (usize, usize), any: &mut u32, all: &mut u32, people: &mut usize) {
+    if *people > 0 {
+        totals.0 += any.count_ones() as usize;
+        totals.1 += all.count_ones() as usize;
+    }
+    *any = 0;
+    *all = u32::MAX;
+    *people = 0;
+}
+
+// accepts the same input as the `lowercase` alphabet
+pub fn count(input: &[u8]) -> Result<(usize, usize), AnswerError> {
+    let mut totals: (usize, usize) = (0, 0);
+    let (mut any, mut all, mut people): (u32, u32, usize) = (0, u32::MAX, 0);
+    let mut person: u32 = 0;
+    let (mut line, mut line_start): (usize, usize) = (1, 0);
+    for (i, &b) in input.iter().enumerate() {
+        match b {
+            b'a'..=b'z' => person |= 1 << (b - b'a'),
+            b'\n' => {
+                if person == 0 {
+                    finish(&mut totals, &mut any, &mut all, &mut people);
+                } else {
+                    any |= person;
+                    all &= person;
+                    people += 1;
+                }
+                person = 0;
+                line += 1;
+                line_start = i + 1;
+            }
+            b' ' | b'\t' | b'\r' => {}
+            _ => {
+                // everything before `b` on this line was ASCII
+                let symbol = String::from_utf8_lossy(&input[i..]).chars().next();
+                return Err(AnswerError::new(
+                    line,
+                    i - line_start + 1,
+                    symbol.unwrap_or_default().to_string(),
+                ));
+            }
+        }
+    }
+    if person != 0 {
+        any |= person;
+        all &= person;
+        people += 1;
+    }
+    finish(&mut totals, &mut any, &mut all, &mut people);
+    Ok(totals)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::super::alphabet::Alphabet;
+    use super::super::tests::TEST_INPUT;
+    use super::super::{anyone, everyone};
+    use super::*;
+
+    #[test]
+    fn test_fast_example() -> Result<(), AnswerError> {
+        assert_eq!(count(TEST_INPUT.as_bytes())?, (11, 6));
+        let crlf = TEST_INPUT.trim_end().replace('\n', "\r\n");
+        assert_eq!(count(crlf.as_bytes())?, (11, 6));
+        let spaced = TEST_INPUT.replace("\n\n", "\n \t\n\n");
+        assert_eq!(count(spaced.as_bytes())?, (11, 6));
+        assert_eq!(count(b"")?, (0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fast_errors() {
+        let e = count("ab\n\nab\naé\n".as_bytes()).unwrap_err();
+        assert_eq!(e, AnswerError::new(4, 2, "é".to_string()));
+        assert!(count(b"abC").is_err());
+    }
+
+    // newline-separated people of up to 26 answers, groups of 1 to 5
+    fn random_forms(bytes: usize) -> String {
+        let mut rng = StdRng::seed_from_u64(6);
+        let mut forms = String::with_capacity(bytes + 64);
+        while forms.len() < bytes {
+            for _ in 0..rng.gen_range(1..=5) {
+                for b in b'a'..=b'z' {
+                    if rng.gen_bool(0.4) {
+                        forms.push(b as char);
+                    }
+                }
+                forms.push('\n');
+            }
+            forms.push('\n');
+        }
+        forms
+    }
+
+    // cargo test --release bench_fast_count -- --ignored --nocapture
+    // with BENCH_MB=4096 for a multi-GB input
+    #[test]
+    #[ignore]
+    fn bench_fast_count() -> Result<(), Box<dyn std::error::Error>> {
+        let mb: usize = std::env::var("BENCH_MB")
+            .map(|s| s.parse())
+            .unwrap_or(Ok(64))?;
+        let forms = random_forms(mb << 20);
+        let alphabet = Alphabet::default();
+
+        let t = Instant::now();
+        let bitvec = (anyone(&forms, &alphabet)?, everyone(&forms, &alphabet)?);
+        println!("bitvec: {:?} for {} MB", t.elapsed(), mb);
+
+        let t = Instant::now();
+        let fast = count(forms.as_bytes())?;
+        println!("u32:    {:?} for {} MB", t.elapsed(), mb);
+        assert_eq!(bitvec, fast);
+        Ok(())
+    }
+}
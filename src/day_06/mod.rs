@@ -1,37 +1,117 @@
+use std::convert::TryFrom;
+
 use bitvec::prelude::*;
 
-fn str_to_bits(s: &str) -> BitArray {
-    let mut a = bitarr![0; 26];
-    for c in s.chars() {
-        if c.is_ascii_alphabetic() {
-            a.set(c as usize - 'a' as usize, true)
+use crate::errors::AOCError;
+use crate::input::blocks;
+
+mod alphabet;
+mod fast;
+mod quorum;
+mod stats;
+
+use alphabet::Alphabet;
+use quorum::{Breakdown, Quorum};
+use stats::Statistics;
+
+// questions anyone in each group answered, summed over groups
+fn anyone(input: &str, alphabet: &Alphabet) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut total: usize = 0;
+    for (lines, g) in blocks(input) {
+        let mut any: BitVec = alphabet.empty();
+        for person in alphabet.group(lines.0, g)? {
+            any |= person;
         }
+        total += any.count_ones();
     }
-    a
+    Ok(total)
+}
+
+// questions everyone in each group answered, summed over groups
+fn everyone(input: &str, alphabet: &Alphabet) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut total: usize = 0;
+    for (lines, g) in blocks(input) {
+        let mut all: BitVec = alphabet.full();
+        for person in alphabet.group(lines.0, g)? {
+            all &= person;
+        }
+        total += all.count_ones();
+    }
+    Ok(total)
 }
 
 pub fn part_1(input: String) -> Result<String, Box<dyn std::error::Error>> {
-    Ok(input
-        .split("\n\n")
-        .map(|g| str_to_bits(g).count_ones())
-        .sum::<usize>()
-        .to_string())
+    Ok(anyone(&input, &Alphabet::default())?.to_string())
 }
 
 pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
-    Ok(input
-        .split("\n\n")
-        .map(|g| {
-            g.trim_end()
-                .split("\n")
-                .map(|l| str_to_bits(l))
-                .fold(bitarr![1; 26], |acc, a| acc & a)
-                .count_ones()
-        })
-        .sum::<usize>()
+    Ok(everyone(&input, &Alphabet::default())?.to_string())
+}
+
+// an alphabet in the format `Alphabet` parses, or lowercase letters
+fn alphabet_from(arg: Option<&String>) -> Result<Alphabet, AOCError<'static>> {
+    match arg {
+        Some(a) => Alphabet::try_from(a.as_str()),
+        None => Ok(Alphabet::default()),
+    }
+}
+
+fn answers(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let alphabet: Alphabet = alphabet_from(args.first())?;
+    Ok(format!(
+        "{}\n{}",
+        anyone(&input, &alphabet)?,
+        everyone(&input, &alphabet)?
+    ))
+}
+
+fn quorum(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let quorum = Quorum::try_from(
+        args.first()
+            .ok_or(AOCError::new("missing argument for quorum"))?
+            .as_str(),
+    )?;
+    let alphabet: Alphabet = alphabet_from(args.get(1))?;
+    Ok(Breakdown::new(quorum, &alphabet, &input)?
+        .to_string()
+        .trim_end()
         .to_string())
 }
 
+fn stats(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let alphabet: Alphabet = alphabet_from(args.get(1))?;
+    let stats = Statistics::new(&alphabet, &input)?;
+    match args.first().map(|s| s.as_str()) {
+        Some("table") => Ok(stats.to_string().trim_end().to_string()),
+        Some("json") => Ok(stats.to_json().trim_end().to_string()),
+        _ => Err(AOCError::new("expected stats format table or json").into()),
+    }
+}
+
+fn fast(input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let (anyone, everyone) = fast::count(input.as_bytes())?;
+    Ok(format!("{}\n{}", anyone, everyone))
+}
+
+// commands: `answers [<alphabet>]`
+//           `quorum <k>|<p>%|majority [<alphabet>]`
+//           `stats table|json [<alphabet>]`
+//           `fast`
+pub fn command(
+    name: &str,
+    args: &[String],
+    input: Option<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let input = input.ok_or(AOCError::new("input file missing"));
+    match name {
+        "answers" => answers(args, input?),
+        "fast" => fast(input?),
+        "quorum" => quorum(args, input?),
+        "stats" => stats(args, input?),
+        _ => Err(AOCError::new("unknown command").into()),
+    }
+}
+
 pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
     Ok(format!(
         "{}\n{}",
@@ -44,7 +124,7 @@ pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n";
+    pub(super) const TEST_INPUT: &str = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n";
 
     #[test]
     fn test_part_1_example() -> Result<(), Box<dyn std::error::Error>> {
@@ -57,4 +137,25 @@ mod tests {
         assert_eq!(part_2(TEST_INPUT.to_string())?.parse::<usize>()?, 6);
         Ok(())
     }
+
+    #[test]
+    fn test_crlf_and_blank_separators() -> Result<(), Box<dyn std::error::Error>> {
+        let crlf = TEST_INPUT.replace('\n', "\r\n");
+        assert_eq!(part_1(crlf.clone())?.parse::<usize>()?, 11);
+        assert_eq!(part_2(crlf)?.parse::<usize>()?, 6);
+        let spaced = TEST_INPUT.trim_end().replace("\n\n", "\n \n");
+        assert_eq!(part_1(spaced.clone())?.parse::<usize>()?, 11);
+        assert_eq!(part_2(spaced)?.parse::<usize>()?, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_alphabets() -> Result<(), Box<dyn std::error::Error>> {
+        let input = "aB3\nB3\n\nZ\n";
+        assert!(part_1(input.to_string()).is_err());
+        let alphabet = Alphabet::try_from("alphanumeric")?;
+        assert_eq!(anyone(input, &alphabet)?, 4);
+        assert_eq!(everyone(input, &alphabet)?, 3);
+        Ok(())
+    }
 }
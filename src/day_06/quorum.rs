@@ -0,0 +1,183 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use bitvec::prelude::*;
+
+use super::alphabet::{Alphabet, AnswerError};
+use crate::errors::AOCError;
+use crate::input::blocks;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Quorum {
+    AtLeast(usize),
+    // rounding up
+    Percent(usize),
+    Majority,
+}
+
+impl Quorum {
+    pub fn required(&self, people: usize) -> usize {
+        match self {
+            Quorum::AtLeast(k) => *k,
+            Quorum::Percent(p) => (p * people).div_ceil(100),
+            Quorum::Majority => people / 2 + 1,
+        }
+    }
+}
+
+impl TryFrom<&str> for Quorum {
+    type Error = AOCError<'static>;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        if string == "majority" {
+            return Ok(Quorum::Majority);
+        }
+        match string.strip_suffix('%') {
+            Some(p) => match p.parse() {
+                Ok(p) if p <= 100 => Ok(Quorum::Percent(p)),
+                _ => Err(AOCError::new("expected a percentage from 0 to 100")),
+            },
+            None => string
+                .parse()
+                .map(Quorum::AtLeast)
+                .map_err(|_| AOCError::new("expected a count, a percentage or majority")),
+        }
+    }
+}
+
+pub struct Group<'a> {
+    alphabet: &'a Alphabet,
+    lines: (usize, usize),
+    people: usize,
+    counts: Vec<usize>,
+}
+
+impl<'a> Group<'a> {
+    pub fn new(
+        alphabet: &'a Alphabet,
+        lines: (usize, usize),
+        group: &str,
+    ) -> Result<Self, AnswerError> {
+        let mut counts: Vec<usize> = vec![0; alphabet.symbols().len()];
+        let people: Vec<BitVec> = alphabet.group(lines.0, group)?;
+        for person in people.iter() {
+            for i in person.iter_ones() {
+                counts[i] += 1;
+            }
+        }
+        Ok(Group {
+            alphabet,
+            lines,
+            people: people.len(),
+            counts,
+        })
+    }
+
+    pub fn people(&self) -> usize {
+        self.people
+    }
+
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    pub fn answered(&self, quorum: Quorum) -> Vec<&'a str> {
+        let required: usize = quorum.required(self.people).max(1);
+        self.alphabet
+            .symbols()
+            .iter()
+            .zip(self.counts.iter())
+            .filter(|(_, &n)| n >= required)
+            .map(|(s, _)| s.as_str())
+            .collect()
+    }
+}
+
+pub struct Breakdown<'a> {
+    quorum: Quorum,
+    groups: Vec<Group<'a>>,
+}
+
+impl<'a> Breakdown<'a> {
+    pub fn new(quorum: Quorum, alphabet: &'a Alphabet, input: &str) -> Result<Self, AnswerError> {
+        Ok(Breakdown {
+            quorum,
+            groups: blocks(input)
+                .into_iter()
+                .map(|(lines, g)| Group::new(alphabet, lines, g))
+                .collect::<Result<Vec<Group>, AnswerError>>()?,
+        })
+    }
+
+    pub fn total(&self) -> usize {
+        self.groups
+            .iter()
+            .map(|g| g.answered(self.quorum).len())
+            .sum()
+    }
+}
+
+impl fmt::Display for Breakdown<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, g) in self.groups.iter().enumerate() {
+            let answered: Vec<&str> = g.answered(self.quorum);
+            writeln!(
+                f,
+                "group {} (lines {}-{}): {} of {} people needed, {} questions: {}",
+                i + 1,
+                g.lines.0,
+                g.lines.1,
+                self.quorum.required(g.people).max(1),
+                g.people,
+                answered.len(),
+                answered.concat()
+            )?;
+        }
+        writeln!(f, "\ntotal: {}", self.total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    #[test]
+    fn test_quorum_matches_parts() -> Result<(), AnswerError> {
+        let alphabet = Alphabet::default();
+        let breakdown = Breakdown::new(Quorum::AtLeast(1), &alphabet, TEST_INPUT)?;
+        assert_eq!(breakdown.total(), 11);
+        let breakdown = Breakdown::new(Quorum::Percent(100), &alphabet, TEST_INPUT)?;
+        assert_eq!(breakdown.total(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quorum_required() -> Result<(), AOCError<'static>> {
+        assert_eq!(Quorum::try_from("majority")?.required(4), 3);
+        assert_eq!(Quorum::try_from("majority")?.required(5), 3);
+        assert_eq!(Quorum::try_from("50%")?.required(5), 3);
+        assert_eq!(Quorum::try_from("50%")?.required(4), 2);
+        assert_eq!(Quorum::try_from("2")?.required(7), 2);
+        assert!(Quorum::try_from("101%").is_err());
+        assert!(Quorum::try_from("most").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_breakdown() -> Result<(), AnswerError> {
+        let alphabet = Alphabet::default();
+        let breakdown = Breakdown::new(Quorum::Majority, &alphabet, "ab\nac\nad\n\nxy\nz\n")?;
+        assert_eq!(breakdown.groups[0].answered(Quorum::Majority), vec!["a"]);
+        assert_eq!(breakdown.total(), 1);
+        assert_eq!(
+            breakdown.to_string(),
+            concat!(
+                "group 1 (lines 1-3): 2 of 3 people needed, 1 questions: a\n",
+                "group 2 (lines 5-6): 2 of 2 people needed, 0 questions: \n",
+                "\ntotal: 1\n",
+            )
+        );
+        Ok(())
+    }
+}
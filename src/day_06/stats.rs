@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::alphabet::{Alphabet, AnswerError};
+use super::quorum::Group;
+use crate::input::blocks;
+use crate::json;
+
+struct Question<'a> {
+    symbol: &'a str,
+    anyone: usize,
+    everyone: usize,
+    individuals: usize,
+}
+
+pub struct Statistics<'a> {
+    questions: Vec<Question<'a>>,
+    group_sizes: BTreeMap<usize, usize>,
+}
+
+impl<'a> Statistics<'a> {
+    pub fn new(alphabet: &'a Alphabet, input: &str) -> Result<Self, AnswerError> {
+        let mut questions: Vec<Question> = alphabet
+            .symbols()
+            .iter()
+            .map(|s| Question {
+                symbol: s,
+                anyone: 0,
+                everyone: 0,
+                individuals: 0,
+            })
+            .collect();
+        let mut group_sizes: BTreeMap<usize, usize> = BTreeMap::new();
+        for (lines, g) in blocks(input) {
+            let group = Group::new(alphabet, lines, g)?;
+            *group_sizes.entry(group.people()).or_insert(0) += 1;
+            for (q, &n) in questions.iter_mut().zip(group.counts()) {
+                q.anyone += (n > 0) as usize;
+                q.everyone += (n == group.people()) as usize;
+                q.individuals += n;
+            }
+        }
+        Ok(Statistics {
+            questions,
+            group_sizes,
+        })
+    }
+
+    // the questions answered by the most (or fewest) individuals, ties and all
+    fn extreme(&self, most: bool) -> Vec<&'a str> {
+        let counts = self.questions.iter().map(|q| q.individuals);
+        let target: Option<usize> = if most { counts.max() } else { counts.min() };
+        self.questions
+            .iter()
+            .filter(|q| Some(q.individuals) == target)
+            .map(|q| q.symbol)
+            .collect()
+    }
+
+    pub fn most_common(&self) -> Vec<&'a str> {
+        self.extreme(true)
+    }
+
+    pub fn least_common(&self) -> Vec<&'a str> {
+        self.extreme(false)
+    }
+
+    pub fn to_json(&self) -> String {
+        let questions: Vec<String> = self
+            .questions
+            .iter()
+            .map(|q| {
+                format!(
+                    "    {{\"question\":{},\"anyone\":{},\"everyone\":{},\"individuals\":{}}}",
+                    json::string(q.symbol),
+                    q.anyone,
+                    q.everyone,
+                    q.individuals
+                )
+            })
+            .collect();
+        let sizes: Vec<String> = self
+            .group_sizes
+            .iter()
+            .map(|(size, n)| format!("\"{}\":{}", size, n))
+            .collect();
+        let list = |symbols: Vec<&str>| -> String {
+            let symbols: Vec<String> = symbols.into_iter().map(json::string).collect();
+            format!("[{}]", symbols.join(","))
+        };
+        format!(
+            "{{\n  \"questions\":[\n{}\n  ],\n  \"group_sizes\":{{{}}},\n  \"most_common\":{},\n  \"least_common\":{}\n}}\n",
+            questions.join(",\n"),
+            sizes.join(","),
+            list(self.most_common()),
+            list(self.least_common())
+        )
+    }
+}
+
+impl fmt::Display for Statistics<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "question  anyone  everyone  individuals")?;
+        for q in self.questions.iter() {
+            writeln!(
+                f,
+                "{:<8}  {:>6}  {:>8}  {:>11}",
+                q.symbol, q.anyone, q.everyone, q.individuals
+            )?;
+        }
+        writeln!(f, "\ngroup size  groups")?;
+        for (size, n) in self.group_sizes.iter() {
+            writeln!(f, "{:>10}  {:>6}", size, n)?;
+        }
+        writeln!(f, "\nmost common: {}", self.most_common().join(" "))?;
+        writeln!(f, "least common: {}", self.least_common().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::super::tests::TEST_INPUT;
+    use super::*;
+
+    #[test]
+    fn test_statistics_example() -> Result<(), AnswerError> {
+        let alphabet = Alphabet::default();
+        let stats = Statistics::new(&alphabet, TEST_INPUT)?;
+        let a = &stats.questions[0];
+        assert_eq!((a.anyone, a.everyone, a.individuals), (4, 3, 8));
+        // summed over questions, the per-group counts are parts 1 and 2
+        assert_eq!(stats.questions.iter().map(|q| q.anyone).sum::<usize>(), 11);
+        assert_eq!(stats.questions.iter().map(|q| q.everyone).sum::<usize>(), 6);
+        assert_eq!(
+            stats.group_sizes.iter().collect::<Vec<_>>(),
+            vec![(&1, &2), (&2, &1), (&3, &1), (&4, &1)]
+        );
+        assert_eq!(stats.most_common(), vec!["a"]);
+        assert_eq!(stats.least_common().len(), 23);
+        Ok(())
+    }
+
+    #[test]
+    fn test_statistics_output() -> Result<(), Box<dyn std::error::Error>> {
+        let alphabet = Alphabet::try_from("x\"")?;
+        let stats = Statistics::new(&alphabet, "x\"\nx\n")?;
+        assert_eq!(
+            stats.to_json(),
+            concat!(
+                "{\n",
+                "  \"questions\":[\n",
+                "    {\"question\":\"x\",\"anyone\":1,\"everyone\":1,\"individuals\":2},\n",
+                "    {\"question\":\"\\\"\",\"anyone\":1,\"everyone\":0,\"individuals\":1}\n",
+                "  ],\n",
+                "  \"group_sizes\":{\"2\":1},\n",
+                "  \"most_common\":[\"x\"],\n",
+                "  \"least_common\":[\"\\\"\"]\n",
+                "}\n",
+            )
+        );
+        assert!(stats
+            .to_string()
+            .contains("\nx              1         1            2\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use crate::errors::AOCError;
+
+pub type BagId = usize;
+
+struct Node<'a> {
+    name: &'a str,
+    // bags directly inside this one, with how many of each
+    contains: Vec<(BagId, usize)>,
+    // bags this one is directly inside, with how many of it they hold
+    contained_in: Vec<(BagId, usize)>,
+}
+
+pub struct BagGraph<'a> {
+    nodes: Vec<Node<'a>>,
+    ids: HashMap<&'a str, BagId>,
+}
+
+impl<'a> BagGraph<'a> {
+    pub fn new() -> Self {
+        BagGraph {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+
+    // added if it isn't in the graph yet
+    pub fn insert(&mut self, name: &'a str) -> BagId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id: BagId = self.nodes.len();
+        self.nodes.push(Node {
+            name,
+            contains: Vec::new(),
+            contained_in: Vec::new(),
+        });
+        self.ids.insert(name, id);
+        id
+    }
+
+    pub fn add_rule(&mut self, outside: BagId, inside: BagId, count: usize) {
+        self.nodes[outside].contains.push((inside, count));
+        self.nodes[inside].contained_in.push((outside, count));
+    }
+
+    pub fn id(&self, name: &str) -> Option<BagId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: BagId) -> &'a str {
+        self.nodes[id].name
+    }
+
+    pub fn contains(&self, id: BagId) -> &[(BagId, usize)] {
+        &self.nodes[id].contains
+    }
+
+    pub fn contained_in(&self, id: BagId) -> &[(BagId, usize)] {
+        &self.nodes[id].contained_in
+    }
+
+    // every bag reachable from `id` along `edges`, in the order first found
+    fn reachable(&self, id: BagId, edges: fn(&Self, BagId) -> &[(BagId, usize)]) -> Vec<BagId> {
+        let mut seen: Vec<bool> = vec![false; self.nodes.len()];
+        let mut found: Vec<BagId> = Vec::new();
+        let mut stack: Vec<BagId> = vec![id];
+        while let Some(next) = stack.pop() {
+            for &(other, _) in edges(self, next) {
+                if !seen[other] {
+                    seen[other] = true;
+                    found.push(other);
+                    stack.push(other);
+                }
+            }
+        }
+        found
+    }
+
+    pub fn ancestors(&self, id: BagId) -> Vec<BagId> {
+        self.reachable(id, Self::contained_in)
+    }
+
+    pub fn descendants(&self, id: BagId) -> Vec<BagId> {
+        self.reachable(id, Self::contains)
+    }
+
+    // each bag after all the bags it holds
+    pub fn post_order(&self, id: BagId) -> Result<Vec<BagId>, AOCError<'static>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            New,
+            Open,
+            Done,
+        }
+        let mut marks: Vec<Mark> = vec![Mark::New; self.nodes.len()];
+        let mut order: Vec<BagId> = Vec::new();
+        // each open bag with the index of the next edge to follow from it
+        let mut stack: Vec<(BagId, usize)> = vec![(id, 0)];
+        marks[id] = Mark::Open;
+        while let Some(&(bag, next)) = stack.last() {
+            match self.contains(bag).get(next) {
+                Some(&(inside, _)) => {
+                    stack.last_mut().unwrap().1 += 1;
+                    match marks[inside] {
+                        Mark::New => {
+                            marks[inside] = Mark::Open;
+                            stack.push((inside, 0));
+                        }
+                        Mark::Open => return Err(AOCError::new("bag rules contain a cycle")),
+                        Mark::Done => {}
+                    }
+                }
+                None => {
+                    marks[bag] = Mark::Done;
+                    order.push(bag);
+                    stack.pop();
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    // errors if the total doesn't fit in a `usize`
+    pub fn total_contents(&self, id: BagId) -> Result<usize, AOCError<'static>> {
+        let overflow = || AOCError::new("bag total overflows");
+        let mut totals: Vec<usize> = vec![0; self.nodes.len()];
+        for bag in self.post_order(id)? {
+            let mut total: usize = 0;
+            for &(inside, count) in self.contains(bag) {
+                let bags: usize = totals[inside]
+                    .checked_add(1)
+                    .and_then(|t| t.checked_mul(count))
+                    .ok_or_else(overflow)?;
+                total = total.checked_add(bags).ok_or_else(overflow)?;
+            }
+            totals[bag] = total;
+        }
+        Ok(totals[id])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::bags_from_input;
+    use super::super::tests::TEST_INPUT_1;
+    use super::*;
+
+    #[test]
+    fn test_graph_queries() -> Result<(), Box<dyn std::error::Error>> {
+        let graph = bags_from_input(TEST_INPUT_1)?;
+        assert_eq!(graph.nodes.len(), 9);
+        let gold = graph.id("shiny gold").ok_or("no shiny gold")?;
+        let names = |ids: Vec<BagId>| {
+            let mut names: Vec<&str> = ids.into_iter().map(|id| graph.name(id)).collect();
+            names.sort_unstable();
+            names
+        };
+        assert_eq!(
+            names(graph.ancestors(gold)),
+            vec!["bright white", "dark orange", "light red", "muted yellow"]
+        );
+        assert_eq!(
+            names(graph.descendants(gold)),
+            vec!["dark olive", "dotted black", "faded blue", "vibrant plum"]
+        );
+        assert_eq!(graph.total_contents(gold)?, 32);
+        let white = graph.id("bright white").ok_or("no bright white")?;
+        assert_eq!(graph.contains(white), &[(gold, 1)]);
+        assert_eq!(graph.contained_in(white).len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycles_terminate() {
+        let mut graph = BagGraph::new();
+        let (a, b, c) = (graph.insert("a"), graph.insert("b"), graph.insert("c"));
+        graph.add_rule(c, a, 1);
+        graph.add_rule(a, b, 1);
+        graph.add_rule(b, a, 2);
+        assert_eq!(graph.ancestors(a), vec![c, b, a]);
+        assert_eq!(graph.descendants(b), vec![a, b]);
+        assert!(graph.total_contents(c).is_err());
+    }
+
+    #[test]
+    fn test_shared_subtrees() -> Result<(), AOCError<'static>> {
+        // a diamond 64 levels deep, which has 2^64 paths to the bottom
+        let names: Vec<String> = (0..=128).map(|i| i.to_string()).collect();
+        let mut graph = BagGraph::new();
+        let ids: Vec<BagId> = names.iter().map(|n| graph.insert(n)).collect();
+        for level in 0..64 {
+            let (top, bottom) = (ids[2 * level], ids[2 * level + 2]);
+            graph.add_rule(top, ids[2 * level + 1], 1);
+            graph.add_rule(top, bottom, 1);
+            graph.add_rule(ids[2 * level + 1], bottom, 1);
+        }
+        assert_eq!(graph.post_order(ids[0])?.len(), 129);
+        assert_eq!(graph.total_contents(ids[126])?, 3);
+        assert_eq!(graph.total_contents(ids[4])?, 3 * ((1 << 62) - 1));
+        assert_eq!(
+            graph.total_contents(ids[0]).err().map(|e| e.to_string()),
+            Some("bag total overflows".to_string())
+        );
+        Ok(())
+    }
+}
@@ -1,20 +1,13 @@
 use regex::Regex;
-use std::collections::HashMap;
 
 use crate::errors::AOCError;
 
-type Bags<'a> = HashMap<&'a str, HashMap<&'a str, usize>>;
+mod graph;
 
-fn contains_bags(bags: &Bags, name: &str, goal: &str) -> bool {
-    bags.get(name)
-        .map(|contained| {
-            contained.contains_key(goal) || contained.keys().any(|k| contains_bags(bags, k, goal))
-        })
-        .unwrap_or(false)
-}
+use graph::{BagGraph, BagId};
 
-fn bags_from_input<'a>(input: &'a String) -> Result<Bags, Box<dyn std::error::Error>> {
-    let mut bags: HashMap<&str, HashMap<&str, usize>> = HashMap::new();
+fn bags_from_input(input: &str) -> Result<BagGraph<'_>, Box<dyn std::error::Error>> {
+    let mut bags: BagGraph = BagGraph::new();
     let bag_outside_regex = Regex::new(r"^(\w+ \w+)").unwrap();
     let bag_inside_regex = Regex::new(r"(\d) (\w+ \w+)").unwrap();
     for l in input.lines() {
@@ -25,41 +18,91 @@ fn bags_from_input<'a>(input: &'a String) -> Result<Bags, Box<dyn std::error::Er
             .get(0)
             .ok_or(e.clone())?
             .as_str();
-        let mut bags_inside: HashMap<&str, usize> = HashMap::new();
+        let outside: BagId = bags.insert(outside);
         for c in bag_inside_regex.captures_iter(l) {
             let n: usize = c.get(1).ok_or(e.clone())?.as_str().parse()?;
-            let b: &str = c.get(2).ok_or(e.clone())?.as_str();
-            bags_inside.insert(b, n);
+            let inside: BagId = bags.insert(c.get(2).ok_or(e.clone())?.as_str());
+            bags.add_rule(outside, inside, n);
         }
-        bags.insert(outside, bags_inside);
     }
     Ok(bags)
 }
 
-// TODO: come back to this with arena + doubly-linked tree
 pub fn part_1(input: String) -> Result<String, Box<dyn std::error::Error>> {
-    let bags: Bags = bags_from_input(&input)?;
-    Ok(bags
-        .keys()
-        .filter(|k| contains_bags(&bags, k, "shiny gold"))
-        .count()
-        .to_string())
+    let bags: BagGraph = bags_from_input(&input)?;
+    let goal: BagId = match bags.id("shiny gold") {
+        Some(id) => id,
+        None => return Ok("0".to_string()),
+    };
+    Ok(bags.ancestors(goal).len().to_string())
 }
 
-fn count_bags(bags: &Bags, start: &str) -> usize {
-    bags.get(start)
-        .map(|contained| {
-            contained
-                .iter()
-                .map(|(bag, count)| count + count * count_bags(bags, bag))
-                .sum()
-        })
-        .unwrap_or(1)
+pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let bags: BagGraph = bags_from_input(&input)?;
+    let start: BagId = bags
+        .id("shiny gold")
+        .ok_or(AOCError::new("no rule for shiny gold bags"))?;
+    Ok(bags.total_contents(start)?.to_string())
 }
 
-pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
-    let bags: Bags = bags_from_input(&input)?;
-    Ok(count_bags(&bags, "shiny gold").to_string())
+// a bag named by the remaining arguments, e.g. `shiny gold`
+fn bag_from<'a>(bags: &BagGraph<'a>, args: &[String]) -> Result<BagId, AOCError<'static>> {
+    if args.is_empty() {
+        return Err(AOCError::new("missing argument for bag"));
+    }
+    bags.id(&args.join(" "))
+        .ok_or(AOCError::new("no rule mentions that bag"))
+}
+
+// bag names, sorted, one per line
+fn names(bags: &BagGraph, ids: Vec<BagId>) -> String {
+    let mut names: Vec<&str> = ids.into_iter().map(|id| bags.name(id)).collect();
+    names.sort_unstable();
+    names.join("\n")
+}
+
+fn ancestors(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let bags: BagGraph = bags_from_input(&input)?;
+    Ok(names(&bags, bags.ancestors(bag_from(&bags, args)?)))
+}
+
+fn descendants(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let bags: BagGraph = bags_from_input(&input)?;
+    Ok(names(&bags, bags.descendants(bag_from(&bags, args)?)))
+}
+
+fn total(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let bags: BagGraph = bags_from_input(&input)?;
+    Ok(bags.total_contents(bag_from(&bags, args)?)?.to_string())
+}
+
+fn parents(args: &[String], input: String) -> Result<String, Box<dyn std::error::Error>> {
+    let bags: BagGraph = bags_from_input(&input)?;
+    let parents: Vec<BagId> = bags
+        .contained_in(bag_from(&bags, args)?)
+        .iter()
+        .map(|&(bag, _)| bag)
+        .collect();
+    Ok(names(&bags, parents))
+}
+
+// commands: `ancestors <bag>`
+//           `descendants <bag>`
+//           `total <bag>`
+//           `parents <bag>`
+pub fn command(
+    name: &str,
+    args: &[String],
+    input: Option<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let input = input.ok_or(AOCError::new("input file missing"));
+    match name {
+        "ancestors" => ancestors(args, input?),
+        "descendants" => descendants(args, input?),
+        "parents" => parents(args, input?),
+        "total" => total(args, input?),
+        _ => Err(AOCError::new("unknown command").into()),
+    }
 }
 
 pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
@@ -74,7 +117,12 @@ pub fn main(input: String) -> Result<String, Box<dyn std::error::Error>> {
 mod tests {
     use super::*;
 
-    const TEST_INPUT_1: &str = concat!(
+    use std::time::Instant;
+
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    pub(super) const TEST_INPUT_1: &str = concat!(
         "light red bags contain 1 bright white bag, 2 muted yellow bags.\n",
         "dark orange bags contain 3 bright white bags, 4 muted yellow bags.\n",
         "bright white bags contain 1 shiny gold bag.\n",
@@ -108,4 +156,62 @@ mod tests {
         assert_eq!(part_2(TEST_INPUT_2.to_string())?.parse::<usize>()?, 126);
         Ok(())
     }
+
+    // rules for `n` bags, each holding up to four of the next thousand, with
+    // shiny gold in the middle
+    fn random_rules(n: usize) -> String {
+        let mut rng = StdRng::seed_from_u64(7);
+        let name = |i: usize| match i {
+            _ if i == n / 2 => "shiny gold".to_string(),
+            _ => format!("tint{} hue", i),
+        };
+        let mut rules = String::new();
+        for i in 0..n {
+            let mut inside: Vec<String> = Vec::new();
+            for _ in 0..rng.gen_range(0..=4) {
+                let j: usize = rng.gen_range(i + 1..i + 1000);
+                if j < n {
+                    inside.push(format!("{} {} bags", rng.gen_range(1..=9), name(j)));
+                }
+            }
+            if inside.is_empty() {
+                rules.push_str(&format!("{} bags contain no other bags.\n", name(i)));
+            } else {
+                rules.push_str(&format!(
+                    "{} bags contain {}.\n",
+                    name(i),
+                    inside.join(", ")
+                ));
+            }
+        }
+        rules
+    }
+
+    // cargo test --release bench_bag_queries -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_bag_queries() -> Result<(), Box<dyn std::error::Error>> {
+        let rules = random_rules(100_000);
+
+        let t = Instant::now();
+        let bags: BagGraph = bags_from_input(&rules)?;
+        println!("parse:     {:?}", t.elapsed());
+
+        let gold: BagId = bags.id("shiny gold").ok_or("no shiny gold")?;
+        let t = Instant::now();
+        let holders: usize = bags.ancestors(gold).len();
+        println!("ancestors: {:?} ({} bags)", t.elapsed(), holders);
+
+        let t = Instant::now();
+        // the totals of bags near the top overflow, which still walks every rule
+        match bags.total_contents(bags.id("tint0 hue").ok_or("no tint0")?) {
+            Ok(total) => println!("total:     {:?} ({} bags)", t.elapsed(), total),
+            Err(e) => println!("total:     {:?} ({})", t.elapsed(), e),
+        }
+
+        let t = Instant::now();
+        let total: usize = bags.total_contents(gold)?;
+        println!("gold:      {:?} ({} bags)", t.elapsed(), total);
+        Ok(())
+    }
 }
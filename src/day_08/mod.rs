@@ -6,6 +6,7 @@ use std::num::TryFromIntError;
 
 #[derive(Clone, Debug)]
 enum ErrorCode {
+    #[allow(dead_code)] // only read through `Debug`
     IPOutOfBounds(usize),
     IntParseError,
     InstructionParseError,
@@ -101,7 +102,7 @@ impl TryFrom<&str> for Program {
             instructions.push(Instruction::try_from(l)?);
         }
         Ok(Program {
-            instructions: instructions,
+            instructions,
             acc: 0,
             ip: 0,
             visited: HashSet::new(),
@@ -132,7 +133,7 @@ impl Program {
                 self.ip += 1;
             }
             Instruction::Jmp(n) => {
-                self.ip = usize::try_from(isize::try_from(self.ip)? as isize + n)?;
+                self.ip = usize::try_from(isize::try_from(self.ip)? + n)?;
             }
         }
         Ok(())
@@ -161,14 +162,12 @@ pub fn part_2(input: String) -> Result<String, Box<dyn std::error::Error>> {
             Instruction::Jmp(n) => p.instructions[i] = Instruction::Nop(n),
             _ => continue,
         };
-        match p.acc_before_loop() {
-            Err(e) => match e.code {
-                Some(ErrorCode::Terminated) => {
-                    return Ok(p.acc.to_string());
-                }
-                _ => {}
-            },
-            _ => {}
+        if let Err(ProgramError {
+            code: Some(ErrorCode::Terminated),
+            ..
+        }) = p.acc_before_loop()
+        {
+            return Ok(p.acc.to_string());
         }
     }
     Err(Box::new(ProgramError::new("couldn't find answer")))
@@ -1,6 +1,6 @@
 use crate::errors::AOCError;
 
-fn xmas_1(ns: &Vec<usize>, window: usize) -> Option<usize> {
+fn xmas_1(ns: &[usize], window: usize) -> Option<usize> {
     for (i, &n) in ns.iter().enumerate() {
         if i < window {
             continue;
@@ -22,7 +22,7 @@ fn xmas_1(ns: &Vec<usize>, window: usize) -> Option<usize> {
     None
 }
 
-fn xmas_2(ns: &Vec<usize>, invalid: usize) -> Option<usize> {
+fn xmas_2(ns: &[usize], invalid: usize) -> Option<usize> {
     let mut sums: Vec<Vec<usize>> = vec![vec![0; ns.len()]; ns.len()];
     // element (i, j) in sums should be ns[i..j].sum()
     //     sums[0][j] == sum of first j numbers in ns
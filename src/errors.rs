@@ -8,7 +8,7 @@ pub struct AOCError<'a> {
 
 impl<'a> AOCError<'a> {
     pub fn new(msg: &'a str) -> Self {
-        Self { msg: msg }
+        Self { msg }
     }
 }
 
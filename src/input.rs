@@ -0,0 +1,54 @@
+// blank-line-separated blocks with their 1-indexed first and last lines
+pub fn blocks(input: &str) -> Vec<((usize, usize), &str)> {
+    let mut blocks: Vec<((usize, usize), &str)> = Vec::new();
+    // line and byte offset where the current block started
+    let mut start: Option<(usize, usize)> = None;
+    let mut end: usize = 0;
+    let mut offset: usize = 0;
+    let mut last: usize = 0;
+    for (i, l) in input.split('\n').enumerate() {
+        let line: usize = i + 1;
+        if l.trim().is_empty() {
+            if let Some((first, from)) = start.take() {
+                blocks.push(((first, last), &input[from..end]));
+            }
+        } else {
+            if start.is_none() {
+                start = Some((line, offset));
+            }
+            end = offset + l.trim_end_matches('\r').len();
+            last = line;
+        }
+        offset += l.len() + 1;
+    }
+    if let Some((first, from)) = start {
+        blocks.push(((first, last), &input[from..end]));
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            blocks("a b\nc\n\nd\n"),
+            vec![((1, 2), "a b\nc"), ((4, 4), "d")]
+        );
+        // no trailing newline, and repeated separators
+        assert_eq!(blocks("\na\n\n\n\nb"), vec![((2, 2), "a"), ((6, 6), "b")]);
+        assert_eq!(blocks(""), vec![]);
+        assert_eq!(blocks(" \n\t\n"), vec![]);
+    }
+
+    #[test]
+    fn test_blocks_crlf_and_whitespace() {
+        assert_eq!(
+            blocks("a\r\nb \r\n  \r\nc\r\n"),
+            vec![((1, 2), "a\r\nb "), ((4, 4), "c")]
+        );
+        assert_eq!(blocks("a\n \t \nb\n"), vec![((1, 1), "a"), ((3, 3), "b")]);
+    }
+}
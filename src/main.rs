@@ -14,6 +14,8 @@ mod day_07;
 mod day_08;
 mod day_09;
 mod errors;
+mod input;
+mod json;
 mod types;
 
 use errors::AOCError;
@@ -66,6 +68,19 @@ fn solve(day: usize, part_opt: Option<usize>) -> Result<String, Box<dyn Error>>
     }
 }
 
+fn command(day: usize, name: &str, args: &[String]) -> Result<String, Box<dyn Error>> {
+    let input: Option<String> = fs::read_to_string(format!("inputs/day_{:02}/input", day)).ok();
+    match day {
+        2 => day_02::command(name, args, input),
+        3 => day_03::command(name, args, input),
+        4 => day_04::command(name, args, input),
+        5 => day_05::command(name, args, input),
+        6 => day_06::command(name, args, input),
+        7 => day_07::command(name, args, input),
+        _ => Err(AOCError::new("not implemented").into()),
+    }
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     let day: usize = env::args()
         .nth(1)
@@ -74,11 +89,13 @@ fn main() -> Result<(), Box<dyn Error>> {
             "missing argument for day",
         ))?
         .parse::<usize>()?;
-    let part: Option<usize> = env::args()
-        .nth(2)
-        .map(|p| p.parse::<usize>().ok())
-        .flatten();
-    let answer: String = solve(day, part)?;
+    let part: Option<usize> = env::args().nth(2).and_then(|p| p.parse::<usize>().ok());
+    let answer: String = match env::args().nth(2) {
+        Some(name) if part.is_none() => {
+            command(day, &name, &env::args().skip(3).collect::<Vec<String>>())?
+        }
+        _ => solve(day, part)?,
+    };
     println!("{}", answer);
     Ok(())
 }